@@ -0,0 +1,265 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, Index, LitStr, Member, Type};
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(String),
+}
+
+fn parse_template(template: &LitStr) -> syn::Result<Vec<Segment>> {
+    let error = |message: &str| syn::Error::new(template.span(), message);
+    let text = template.value();
+    let mut chars = text.chars().peekable();
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                if name.is_empty() {
+                    return Err(error("empty placeholder, name a field like {x} or {0}"));
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                } else if matches!(segments.last(), Some(Segment::Placeholder(_))) {
+                    return Err(error("placeholders must be separated by literal text"));
+                }
+                segments.push(Segment::Placeholder(name));
+            }
+            '}' => return Err(error("unmatched `}` in format, use `}}` for a literal brace")),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+fn format_attr(attrs: &[Attribute], span: Span) -> syn::Result<LitStr> {
+    let mut format = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown aoc attribute, expected `format`"))
+            }
+        })?;
+    }
+
+    format.ok_or_else(|| syn::Error::new(span, "missing #[aoc(format = \"...\")]"))
+}
+
+fn member_matches(member: &Member, name: &str) -> bool {
+    match member {
+        Member::Named(ident) => ident == name,
+        Member::Unnamed(index) => index.index.to_string() == name,
+    }
+}
+
+const INTEGERS: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The `Parser` call reading a value of `ty` by its own syntax, for the
+/// primitive numeric types. Those fields stop where the number does, so they
+/// may contain the next literal, as a negative number does before a `-`.
+fn scanner_for(ty: &Type) -> Option<TokenStream> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    if INTEGERS.contains(&ident.as_str()) {
+        Some(quote! { __parser.try_int::<#ty>()? })
+    } else if ident == "f64" {
+        Some(quote! { __parser.try_f64()? })
+    } else {
+        None
+    }
+}
+
+/// Builds a closure parsing one format into `constructor { fields }`.
+fn parse_fields(format: &LitStr, constructor: TokenStream, fields: &Fields) -> syn::Result<TokenStream> {
+    let segments = parse_template(format)?;
+    let error = |message: String| syn::Error::new(format.span(), message);
+
+    let members: Vec<(Member, &Type)> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => (Member::Named(ident.clone()), &f.ty),
+            None => (Member::Unnamed(Index::from(i)), &f.ty),
+        })
+        .collect();
+
+    // one slot per field, or one per element of a tuple field
+    let mut slots: Vec<Vec<Option<Ident>>> = members
+        .iter()
+        .map(|(_, ty)| match ty {
+            Type::Tuple(tuple) => vec![None; tuple.elems.len().max(1)],
+            _ => vec![None],
+        })
+        .collect();
+
+    let mut steps = Vec::new();
+
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! { __parser.expect_str(#literal)?; }),
+            Segment::Placeholder(name) => {
+                let (field, element) = match name.split_once('.') {
+                    Some((field, element)) => {
+                        let element = element
+                            .parse::<usize>()
+                            .map_err(|_| error(format!("`{name}` should index a tuple like {{{field}.0}}")))?;
+                        (field, Some(element))
+                    }
+                    None => (name.as_str(), None),
+                };
+
+                let index = members
+                    .iter()
+                    .position(|(m, _)| member_matches(m, field))
+                    .ok_or_else(|| error(format!("no field named `{field}`")))?;
+                let field_ty = members[index].1;
+
+                let (slot, ty) = match (element, field_ty) {
+                    (None, ty) => (0, ty),
+                    (Some(e), Type::Tuple(tuple)) if e < tuple.elems.len() => (e, &tuple.elems[e]),
+                    (Some(_), _) => return Err(error(format!("`{name}` is not an element of a tuple field"))),
+                };
+
+                if element.is_none() && slots[index].len() > 1 {
+                    return Err(error(format!("tuple field `{field}` must be filled element by element, like {{{field}.0}}")));
+                }
+                if slots[index][slot].is_some() {
+                    return Err(error(format!("`{name}` appears more than once")));
+                }
+
+                let local = format_ident!("__field_{}_{}", index, slot);
+                let value = scanner_for(ty).unwrap_or_else(|| {
+                    let delim = match segments.get(i + 1) {
+                        Some(Segment::Literal(literal)) => quote! { Some(#literal) },
+                        _ => quote! { None },
+                    };
+                    quote! { __parser.parse_until(#delim)? }
+                });
+                steps.push(quote! { let #local: #ty = #value; });
+                slots[index][slot] = Some(local);
+            }
+        }
+    }
+
+    let mut inits = Vec::new();
+    for ((member, ty), slots) in members.iter().zip(&slots) {
+        let locals = slots
+            .iter()
+            .map(|s| s.clone().ok_or_else(|| error(format!("field `{}` is missing from the format", quote!(#member)))))
+            .collect::<syn::Result<Vec<_>>>()?;
+        let value = match ty {
+            Type::Tuple(_) => quote! { (#(#locals,)*) },
+            _ => quote! { #(#locals)* },
+        };
+        inits.push(quote! { #member: #value });
+    }
+
+    let body = match fields {
+        Fields::Unit => quote! { #constructor },
+        _ => quote! { #constructor { #(#inits),* } },
+    };
+
+    Ok(quote! {
+        |__parser: &mut ::aoc_rs::parser::Parser<'_>| -> ::std::result::Result<Self, ::aoc_rs::parser::ParseError> {
+            #(#steps)*
+            __parser.expect_done()?;
+            Ok(#body)
+        }
+    })
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let parse = match &input.data {
+        Data::Struct(data) => {
+            let format = format_attr(&input.attrs, name.span())?;
+            let parser = parse_fields(&format, quote!(Self), &data.fields)?;
+            quote! { (#parser)(&mut __parser) }
+        }
+        Data::Enum(data) => {
+            let alternatives = data
+                .variants
+                .iter()
+                .map(|v| {
+                    let format = format_attr(&v.attrs, v.ident.span())?;
+                    let ident = &v.ident;
+                    parse_fields(&format, quote!(Self::#ident), &v.fields)
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { __parser.alt(&mut [#(&mut #alternatives),*]) }
+        }
+        Data::Union(_) => return Err(syn::Error::new(name.span(), "AocParse cannot be derived for unions")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::aoc_rs::parser::ParseError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut __parser = ::aoc_rs::parser::Parser::new(s);
+                #parse
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(s: &str) -> syn::Result<Vec<Segment>> {
+        parse_template(&LitStr::new(s, Span::call_site()))
+    }
+
+    #[test]
+    fn test_template() {
+        use Segment::*;
+
+        assert_eq!(
+            template("#{number} @ {point.0},{point.1}").unwrap(),
+            vec![
+                Literal("#".into()),
+                Placeholder("number".into()),
+                Literal(" @ ".into()),
+                Placeholder("point.0".into()),
+                Literal(",".into()),
+                Placeholder("point.1".into()),
+            ],
+        );
+        assert_eq!(template("{{{0}}}").unwrap(), vec![Literal("{".into()), Placeholder("0".into()), Literal("}".into())]);
+    }
+
+    #[test]
+    fn test_bad_templates() {
+        assert!(template("{a}{b}").is_err());
+        assert!(template("{}").is_err());
+        assert!(template("a}").is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// The capture group a field reads from: its own name, unless overridden
+/// with `#[reparse(group = "...")]`.
+fn group_name(field: &syn::Field) -> syn::Result<String> {
+    let mut name = field.ident.as_ref().map(|i| i.to_string().trim_start_matches("r#").to_owned());
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("reparse")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("group") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown reparse attribute, expected `group`"))
+            }
+        })?;
+    }
+
+    name.ok_or_else(|| syn::Error::new_spanned(field, "tuple fields need #[reparse(group = \"...\")]"))
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(syn::Error::new(name.span(), "FromCaptures can only be derived for structs")),
+    };
+
+    let groups = fields.iter().map(group_name).collect::<syn::Result<Vec<_>>>()?;
+
+    let inits = fields
+        .iter()
+        .zip(&groups)
+        .enumerate()
+        .map(|(i, (field, group))| {
+            let ty = &field.ty;
+            let value = quote! {
+                ::aoc_rs::regexparser::parse_named::<#ty>(captures, regex, #group, input)?
+            };
+            Ok(match &field.ident {
+                Some(ident) => quote! { #ident: #value },
+                None => {
+                    let index = syn::Index::from(i);
+                    quote! { #index: #value }
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let body = match fields {
+        Fields::Unit => quote! { Self },
+        _ => quote! { Self { #(#inits),* } },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::aoc_rs::regexparser::FromCaptures for #name #ty_generics #where_clause {
+            const GROUPS: &'static [&'static str] = &[#(#groups),*];
+
+            fn from_captures(
+                captures: &::aoc_rs::regexparser::Captures,
+                regex: &::aoc_rs::regexparser::Regex,
+                input: &str,
+            ) -> ::std::result::Result<Self, ::aoc_rs::regexparser::ReparseError> {
+                Ok(#body)
+            }
+        }
+    })
+}
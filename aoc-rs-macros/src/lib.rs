@@ -0,0 +1,119 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+mod aoc_parse;
+mod from_captures;
+mod reparse;
+mod scan;
+
+/// Derives `FromStr` from a format template such as
+/// `#[aoc(format = "#{number} @ {x},{y}: {w}x{h}")]`.
+///
+/// Placeholders name a field (`{x}`), a tuple field (`{0}`) or an element of
+/// a tuple-typed field (`{point.0}`). Integer and `f64` fields are read as
+/// numbers, so `{lo}-{hi}` accepts `-3--1`. Any other type is parsed with
+/// `FromStr` from the text up to the first occurrence of the next literal
+/// segment, so its value can't contain that literal. On enums every variant
+/// carries its own format and the first one that matches the whole input
+/// wins.
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    aoc_parse::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `aoc_rs::regexparser::FromCaptures`, filling each field from the
+/// named capture group of the same name, or the one given by
+/// `#[reparse(group = "...")]`.
+#[proc_macro_derive(FromCaptures, attributes(reparse))]
+pub fn derive_from_captures(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_captures::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Parses the capture groups of a regex match into a tuple, see
+/// `aoc_rs::regexparser`. The regex is compiled while building, and a
+/// capture group count that doesn't match the type list is a compile error.
+#[proc_macro]
+pub fn try_reparse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseInput);
+    reparse::expand(input, quote!(::aoc_rs::__try_reparse))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// [`try_reparse!`], panicking on failure.
+#[proc_macro]
+pub fn reparse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseInput);
+    reparse::expand(input, quote!(::aoc_rs::__reparse))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// [`try_reparse!`] over every non-overlapping match.
+#[proc_macro]
+pub fn try_reparse_all(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseInput);
+    reparse::expand(input, quote!(::aoc_rs::__try_reparse_all))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// [`try_reparse_all!`], panicking on failure.
+#[proc_macro]
+pub fn reparse_all(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseInput);
+    reparse::expand(input, quote!(::aoc_rs::__reparse_all))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Parses a regex match into a type deriving `FromCaptures`, see
+/// `aoc_rs::regexparser`. The regex is compiled while building, and one
+/// missing a named group the type reads is a compile error.
+#[proc_macro]
+pub fn try_reparse_into(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseIntoInput);
+    reparse::expand_into(input, quote!(::aoc_rs::__try_reparse_into))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// [`try_reparse_into!`], panicking on failure.
+#[proc_macro]
+pub fn reparse_into(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseIntoInput);
+    reparse::expand_into(input, quote!(::aoc_rs::__reparse_into))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Parses every line of the input with [`try_reparse!`], returning a
+/// `Result<Vec<_>, _>` whose error carries the 1-based line number. Blank
+/// lines are skipped when called as `reparse_lines!(types, regex, input,
+/// skip_blank)`.
+#[proc_macro]
+pub fn reparse_lines(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as reparse::ReparseInput);
+    reparse::expand_lines(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Matches a string against a format with `{}` placeholders, see
+/// `aoc_rs::scan`; `{{` and `}}` match literal braces. A format with no
+/// placeholders, a placeholder count that doesn't match the type list, or
+/// two placeholders with no literal text between them, is a compile error.
+#[proc_macro]
+pub fn scan(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as scan::ScanInput);
+    scan::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
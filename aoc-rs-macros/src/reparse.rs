@@ -0,0 +1,176 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, LitStr, Token, Type,
+};
+
+pub struct ReparseInput {
+    /// Slot types, such as `Option<u32>` or `Vec<u8, " ">`, with commas
+    /// inside generics, tuples and `fn` types left alone.
+    slots: Punctuated<Type, Token![,]>,
+    regex: LitStr,
+    string: Expr,
+    skip_blank: bool,
+}
+
+impl Parse for ReparseInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let slots = Punctuated::parse_terminated(&content)?;
+        input.parse::<Token![,]>()?;
+        let regex = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let string = input.parse()?;
+
+        let mut skip_blank = false;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let option: Ident = input.parse()?;
+            if option != "skip_blank" {
+                return Err(syn::Error::new(option.span(), "expected `skip_blank`"));
+            }
+            skip_blank = true;
+            input.parse::<Option<Token![,]>>()?;
+        }
+
+        Ok(Self { slots, regex, string, skip_blank })
+    }
+}
+
+/// Checks the regex compiles and has one capture group per slot.
+fn check(input: &ReparseInput) -> syn::Result<()> {
+    let regex = &input.regex;
+    let compiled = regex::Regex::new(&regex.value())
+        .map_err(|e| syn::Error::new(regex.span(), format!("invalid regex: {e}")))?;
+
+    let groups = compiled.captures_len() - 1;
+    let types = input.slots.len();
+    if groups != types {
+        let message = format!("regex has {groups} capture group(s) but {types} type(s) were given");
+        return Err(syn::Error::new(regex.span(), message));
+    }
+
+    Ok(())
+}
+
+/// Checks the call, then hands it on to `implementation`.
+pub fn expand(input: ReparseInput, implementation: TokenStream) -> syn::Result<TokenStream> {
+    check(&input)?;
+    if input.skip_blank {
+        return Err(syn::Error::new(input.regex.span(), "skip_blank only applies to reparse_lines!"));
+    }
+
+    let ReparseInput { slots, regex, string, .. } = input;
+    Ok(quote! { #implementation!((#slots), #regex, #string) })
+}
+
+pub fn expand_lines(input: ReparseInput) -> syn::Result<TokenStream> {
+    check(&input)?;
+
+    let ReparseInput { slots, regex, string, skip_blank } = input;
+    Ok(quote! { ::aoc_rs::__reparse_lines!(#skip_blank, (#slots), #regex, #string) })
+}
+
+pub struct ReparseIntoInput {
+    ty: Type,
+    regex: LitStr,
+    string: Expr,
+}
+
+impl Parse for ReparseIntoInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let regex = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let string = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { ty, regex, string })
+    }
+}
+
+/// Compiles the regex, then hands the call on to `implementation` behind a
+/// const assertion that the regex has every named group the type reads. The
+/// assertion is an inline `const` block rather than a `const` item so that
+/// it may name the enclosing function's generic parameters, as in
+/// `reparse_into!(Pair<T>, ...)`; for those it fails when the function is
+/// instantiated.
+pub fn expand_into(input: ReparseIntoInput, implementation: TokenStream) -> syn::Result<TokenStream> {
+    let ReparseIntoInput { ty, regex, string } = input;
+    let compiled = regex::Regex::new(&regex.value())
+        .map_err(|e| syn::Error::new(regex.span(), format!("invalid regex: {e}")))?;
+
+    let names: Vec<&str> = compiled.capture_names().flatten().collect();
+    let message = format!(
+        "regex is missing a named capture group that `{}` reads; it has: {}",
+        quote!(#ty).to_string().replace(' ', ""),
+        if names.is_empty() { "none".to_string() } else { names.join(", ") },
+    );
+
+    Ok(quote! {
+        {
+            const {
+                ::std::assert!(
+                    ::aoc_rs::regexparser::has_groups(
+                        <#ty as ::aoc_rs::regexparser::FromCaptures>::GROUPS,
+                        &[#(#names),*],
+                    ),
+                    #message,
+                )
+            };
+            #implementation!(#ty, #regex, #string)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_slots() {
+        for (slots, count) in [
+            ("", 0),
+            ("i32", 1),
+            ("i32,", 1),
+            ("String, Option<u32>, Vec<u8, \" \">", 3),
+            ("(i32, i32), Vec<Vec<u8>>", 2),
+            ("fn(u8) -> u8, Vec<fn(u8, u8) -> u8>", 2),
+        ] {
+            let input: ReparseInput = syn::parse_str(&format!("({slots}), \"\", line")).unwrap();
+            assert_eq!(input.slots.len(), count, "{slots}");
+        }
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let input: ReparseInput = syn::parse_str(r#"(i32, i32), r"(\d+)", line"#).unwrap();
+        let error = expand(input, quote!(imp)).unwrap_err();
+        assert_eq!(error.to_string(), "regex has 1 capture group(s) but 2 type(s) were given");
+
+        let input: ReparseInput = syn::parse_str(r#"(i32,), r"(\d+", line"#).unwrap();
+        let error = expand(input, quote!(imp)).unwrap_err();
+        assert!(error.to_string().starts_with("invalid regex"));
+
+        let input: ReparseInput = syn::parse_str(r#"(i32,), r"(\d+)", line, skip_blank"#).unwrap();
+        assert!(input.skip_blank);
+        assert!(expand(input, quote!(imp)).is_err());
+
+        assert!(syn::parse_str::<ReparseInput>(r#"(i32,), r"(\d+)", line, skip"#).is_err());
+    }
+
+    #[test]
+    fn test_expand_into() {
+        let input: ReparseIntoInput = syn::parse_str(r#"Move, r"(?P<count>\d+) (?P<from>\d+)", line"#).unwrap();
+        let output = expand_into(input, quote!(imp)).unwrap().to_string();
+        assert!(output.contains(r#"& ["count" , "from"]"#), "{output}");
+        assert!(output.contains("`Move` reads; it has: count, from"), "{output}");
+
+        let input: ReparseIntoInput = syn::parse_str(r#"Move, r"(?P<count>\d+", line"#).unwrap();
+        let error = expand_into(input, quote!(imp)).unwrap_err();
+        assert!(error.to_string().starts_with("invalid regex"));
+    }
+}
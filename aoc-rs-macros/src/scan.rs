@@ -0,0 +1,118 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, LitStr, Token, Type,
+};
+
+pub struct ScanInput {
+    format: LitStr,
+    string: Expr,
+    types: Punctuated<Type, Token![,]>,
+}
+
+impl Parse for ScanInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let format = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let string = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let types = Punctuated::parse_terminated(input)?;
+        Ok(Self { format, string, types })
+    }
+}
+
+/// Splits the format into the literal text around its `{}` placeholders,
+/// with `{{` and `}}` standing for literal braces. Each placeholder must be
+/// followed by literal text or the end of the format.
+fn literals(format: &LitStr) -> syn::Result<Vec<String>> {
+    let error = |message: &str| syn::Error::new(format.span(), message);
+    let text = format.value();
+    let mut chars = text.chars().peekable();
+    let mut literals = vec![String::new()];
+
+    while let Some(c) = chars.next() {
+        let literal = literals.last_mut().expect("always one literal");
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' if chars.peek() == Some(&'}') => {
+                chars.next();
+                if literal.is_empty() && literals.len() > 1 {
+                    return Err(error("scan! placeholders must be separated by literal text"));
+                }
+                literals.push(String::new());
+            }
+            '{' => return Err(error("scan! placeholders are written `{}`, use `{{` for a literal brace")),
+            '}' => return Err(error("unmatched `}` in format, use `}}` for a literal brace")),
+            c => literal.push(c),
+        }
+    }
+
+    if literals.len() == 1 {
+        return Err(error("scan! format has no `{}` placeholders"));
+    }
+
+    Ok(literals)
+}
+
+pub fn expand(input: ScanInput) -> syn::Result<TokenStream> {
+    let ScanInput { format, string, types } = input;
+    let literals = literals(&format)?;
+
+    let placeholders = literals.len() - 1;
+    if placeholders != types.len() {
+        let message = format!("format has {placeholders} placeholder(s) but {} type(s) were given", types.len());
+        return Err(syn::Error::new(format.span(), message));
+    }
+
+    let first = &literals[0];
+    let literals = &literals[1..];
+    let types = types.iter();
+    Ok(quote! { ::aoc_rs::__scan!(#string => #first #(, #types => #literals)*) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_errors() {
+        let input: ScanInput = syn::parse_str(r#""{} {}", line => usize"#).unwrap();
+        let error = expand(input).unwrap_err();
+        assert_eq!(error.to_string(), "format has 2 placeholder(s) but 1 type(s) were given");
+
+        let input: ScanInput = syn::parse_str(r#""{}", line => usize, usize,"#).unwrap();
+        assert!(expand(input).is_err());
+
+        let input: ScanInput = syn::parse_str(r#""{}{}", line => usize, usize"#).unwrap();
+        let error = expand(input).unwrap_err();
+        assert_eq!(error.to_string(), "scan! placeholders must be separated by literal text");
+
+        let input: ScanInput = syn::parse_str(r#""{}-{}", line.trim() => u8, u8"#).unwrap();
+        assert!(expand(input).is_ok());
+
+        let input: ScanInput = syn::parse_str(r#""done", line =>"#).unwrap();
+        let error = expand(input).unwrap_err();
+        assert_eq!(error.to_string(), "scan! format has no `{}` placeholders");
+
+        let input: ScanInput = syn::parse_str(r#""{x}", line => u8"#).unwrap();
+        assert!(expand(input).is_err());
+    }
+
+    #[test]
+    fn test_brace_escapes() {
+        let format: LitStr = syn::parse_str(r#""{{{}}} is {}""#).unwrap();
+        assert_eq!(literals(&format).unwrap(), ["{", "} is ", ""]);
+
+        let format: LitStr = syn::parse_str(r#""{{}}""#).unwrap();
+        assert!(literals(&format).is_err());
+    }
+}
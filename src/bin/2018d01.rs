@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use std::iter::once;
+
+use aoc_rs::cycle::first_repeat;
 
 fn main() -> anyhow::Result<()> {
     let input = aoc_rs::get_input(2018, 1)?;
@@ -22,17 +24,11 @@ fn part1(input: &[i64]) -> i64 {
 }
 
 fn part2(input: &[i64]) -> i64 {
-    let mut reached = HashSet::new();
-    let mut sum = 0;
-    loop {
-        for v in input {
-            sum += v;
-            if reached.contains(&sum) {
-                return sum;
-            }
-            reached.insert(sum);
-        }
-    }
+    let sums = input.iter().cycle().scan(0, |sum, change| {
+        *sum += change;
+        Some(*sum)
+    });
+    first_repeat(once(0).chain(sums)).unwrap()
 }
 
 #[cfg(test)]
@@ -56,5 +52,9 @@ mod tests {
     fn test_part2() {
         let numbers = parse(SAMPLE);
         assert_eq!(part2(&numbers), 2);
+
+        for (sample, repeat) in [("+1 -1", 0), ("+3 +3 +4 -2 -4", 10), ("-6 +3 +8 +5 -6", 5), ("+7 +7 -2 -7 -4", 14)] {
+            assert_eq!(part2(&parse(sample)), repeat, "{sample}");
+        }
     }
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
-use std::collections::{HashMap, hash_map::Entry};
-use aoc_rs::get_input;
+use aoc_rs::{counter::Counter, get_input};
 use itertools::Itertools;
 
 fn main() -> anyhow::Result<()> {
@@ -12,26 +11,19 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn count_letters(str: &str) -> HashMap<char, usize> {
-    let mut map = HashMap::<char, usize>::new();
-
-    str.chars().for_each(|c| match map.entry(c) {
-        Entry::Occupied(o) => { *o.into_mut() += 1 }
-        Entry::Vacant(v) => { v.insert(1); }
-    });
-
-    map
+fn count_letters(str: &str) -> Counter<char> {
+    str.chars().collect()
 }
 
 fn part1(ids: &[&str]) -> usize {
-    let counts = ids.iter().map(|s| count_letters(*s));
+    let counts = ids.iter().map(|s| count_letters(s));
 
     let mut twos = 0;
     let mut threes = 0;
 
     for count in counts {
-        if count.values().any(|&v| v == 2) { twos += 1 }
-        if count.values().any(|&v| v == 3) { threes += 1 }
+        if count.values().any(|v| v == 2) { twos += 1 }
+        if count.values().any(|v| v == 3) { threes += 1 }
     }
 
     twos * threes
@@ -1,54 +1,19 @@
-use std::{str::FromStr, collections::{HashMap, hash_map::Entry}};
-use aoc_rs::{get_input, parser::Parser};
+use aoc_rs::{counter::Counter, get_input, point::Point2, AocParse};
 
-#[derive(Debug)]
+#[derive(Debug, AocParse)]
+#[aoc(format = "#{number} @ {corner}: {size.0}x{size.1}")]
 struct Claim {
     number: u64,
-    point: (u64, u64),
+    corner: Point2<u64>,
     size: (u64, u64),
 }
 
 impl Claim {
-    fn top(&self) -> u64 {
-        self.point.0
-    }
-
-    fn bottom(&self) -> u64 {
-        self.point.0 + self.size.0
-    }
-
-    fn left(&self) -> u64 {
-        self.point.1
-    }
-
-    fn right(&self) -> u64 {
-        self.point.1 + self.size.1
-    }
-
-    fn points(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
-        (self.left()..self.right())
-            .flat_map(move |px| (self.top()..self.bottom())
-                .map(move |py| (px, py)))
-    }
-}
-
-impl FromStr for Claim {
-    type Err = anyhow::Error;
-
-    fn from_str(str: &str) -> Result<Self, Self::Err> {
-        let mut parser = Parser::new(str);
-        parser.eat('#');
-        let number = parser.u64();
-        parser.eat_str(" @ ");
-        let ox = parser.u64();
-        parser.eat(',');
-        let oy = parser.u64();
-        parser.eat_str(": ");
-        let sx = parser.u64();
-        parser.eat('x');
-        let sy = parser.u64();
-
-        Ok(Self { number, point: (ox, oy), size: (sx, sy) })
+    fn points(&self) -> impl Iterator<Item = Point2<u64>> + '_ {
+        let (width, height) = self.size;
+        (self.corner.x..self.corner.x + width)
+            .flat_map(move |x| (self.corner.y..self.corner.y + height)
+                .map(move |y| Point2::new(x, y)))
     }
 }
 
@@ -70,23 +35,12 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn overlap_counts(claims: &[Claim]) -> HashMap<(u64, u64), i32> {
-    let mut points = HashMap::new();
-
-    for claim in claims {
-        for point in claim.points() {
-            match points.entry(point) {
-                Entry::Occupied(o) => { *o.into_mut() += 1 }
-                Entry::Vacant(v) => { v.insert(1); }
-            }
-        }
-    }
-
-    points
+fn overlap_counts(claims: &[Claim]) -> Counter<Point2<u64>> {
+    claims.iter().flat_map(Claim::points).collect()
 }
 
 fn part1(claims: &[Claim]) -> usize {
-    overlap_counts(claims).values().filter(|&&v| v > 1).count()
+    overlap_counts(claims).values().filter(|&v| v > 1).count()
 }
 
 fn part2(claims: &[Claim]) -> Option<u64> {
@@ -94,7 +48,7 @@ fn part2(claims: &[Claim]) -> Option<u64> {
 
     'next_claim: for claim in claims {
         for point in claim.points() {
-            if overlap_counts[&point] != 1 {
+            if overlap_counts.get(&point) != 1 {
                 continue 'next_claim
             }
         }
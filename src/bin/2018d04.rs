@@ -1,5 +1,4 @@
-use std::{collections::{HashMap, hash_map::Entry}};
-use aoc_rs::{get_input, parser::Parser};
+use aoc_rs::{counter::Counter, get_input, parser::Parser};
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -8,7 +7,7 @@ enum SleepWake {
     Wake
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
 struct Guard(i64);
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -43,17 +42,16 @@ fn parse(input: &str) -> Vec<LogLine> {
         let _hour = parser.i64();
         parser.eat(':');
         let minute = parser.i64();
-        parser.eat(']');
-
-        if line.contains("Guard") {
-            parser.eat_str(" Guard #");
-            guard = Guard(parser.i64());
-        } else if line.contains("falls") {
-            log.push(LogLine { guard, minute, state: SleepWake::Sleep });
-        } else if line.contains("wakes") {
-            log.push(LogLine { guard, minute, state: SleepWake::Wake });
-        } else {
-            assert!(false);
+        parser.eat_str("] ");
+
+        match parser.word() {
+            "Guard" => {
+                parser.eat_str(" #");
+                guard = Guard(parser.i64());
+            }
+            "falls" => log.push(LogLine { guard, minute, state: SleepWake::Sleep }),
+            "wakes" => log.push(LogLine { guard, minute, state: SleepWake::Wake }),
+            _ => unreachable!("unknown log line {line:?}"),
         }
     }
 
@@ -61,8 +59,7 @@ fn parse(input: &str) -> Vec<LogLine> {
 }
 
 fn part1(log: &[LogLine]) -> i64 {
-    let mut sleep_amounts = HashMap::new();
-
+    let mut sleep_amounts = Counter::new();
     let mut sleep_start = 0;
 
     for line in log {
@@ -70,21 +67,12 @@ fn part1(log: &[LogLine]) -> i64 {
             sleep_start = line.minute;
             continue
         }
-        let sleep_time = line.minute - sleep_start;
-
-        match sleep_amounts.entry(line.guard) {
-            Entry::Occupied(o) => { *o.into_mut() += sleep_time; }
-            Entry::Vacant(v) => { v.insert(sleep_time); }
-        }
+        sleep_amounts.insert_n(line.guard, (line.minute - sleep_start) as usize);
     }
 
-    let sleepiest_guard = *sleep_amounts
-        .iter()
-        .max_by_key(|(_k, &v)| v)
-        .unwrap()
-        .0;
+    let sleepiest_guard = *sleep_amounts.max_by_count().unwrap().0;
 
-    let mut sleep_minutes = [0; 60];
+    let mut sleep_minutes = Counter::new();
 
     for line in log {
         if line.guard != sleepiest_guard {
@@ -96,23 +84,16 @@ fn part1(log: &[LogLine]) -> i64 {
             continue
         }
 
-        for index in (sleep_start as usize)..(line.minute as usize) {
-            sleep_minutes[index] += 1;
-        }
+        sleep_minutes.extend(sleep_start..line.minute);
     }
 
-    let sleepiest_minute = sleep_minutes
-        .iter()
-        .enumerate()
-        .max_by_key(|(_i, &v)| v)
-        .unwrap()
-        .0 as i64;
+    let sleepiest_minute = sleep_minutes.max_by_count().unwrap().0;
 
     sleepiest_guard.0 * sleepiest_minute
 }
 
 fn part2(log: &[LogLine]) -> i64 {
-    let mut guard_sleep_minutes = HashMap::new();
+    let mut guard_sleep_minutes = Counter::new();
     let mut sleep_start = 0;
 
     for line in log {
@@ -121,31 +102,12 @@ fn part2(log: &[LogLine]) -> i64 {
             continue;
         }
 
-        let sleep_minutes_entry = guard_sleep_minutes.entry(line.guard);
-        if let Entry::Vacant(v) = sleep_minutes_entry {
-            v.insert([0; 60]);
-        }
-        let sleep_minutes;
-        if let Entry::Occupied(sleep_minutes_entry) = guard_sleep_minutes.entry(line.guard) {
-            sleep_minutes = sleep_minutes_entry.into_mut();
-        } else {
-            todo!()
-        }
-
-        for index in (sleep_start as usize)..(line.minute as usize) {
-            sleep_minutes[index] += 1;
-        }
-    }
-
-    let mut guard_sleepiest_minutes = HashMap::new();
-    for (guard, minutes) in guard_sleep_minutes {
-        let sleepiest_minute = minutes.iter().enumerate().max_by_key(|(_k, &v)| v).unwrap();
-        guard_sleepiest_minutes.insert(guard, (sleepiest_minute.0, *sleepiest_minute.1));
+        guard_sleep_minutes.extend((sleep_start..line.minute).map(|minute| (line.guard, minute)));
     }
 
-    let sleepiest_guard_minute = guard_sleepiest_minutes.iter().max_by_key(|(_g, &(_m, n))| n).unwrap();
+    let (guard, minute) = guard_sleep_minutes.max_by_count().unwrap().0;
 
-    sleepiest_guard_minute.0.0 * sleepiest_guard_minute.1.0 as i64
+    guard.0 * minute
 }
 
 #[cfg(test)]
@@ -12,7 +12,7 @@ fn main() -> anyhow::Result<()> {
 
 fn react(polymer: &mut dyn Iterator<Item = char>) -> String {
     fn can_react(a: char, b: char) -> bool {
-        a != b && a.to_ascii_lowercase() == b.to_ascii_lowercase()
+        a != b && a.eq_ignore_ascii_case(&b)
     }
 
     let mut output = String::new();
@@ -32,7 +32,7 @@ fn part1(polymer: &str) -> usize {
 }
 
 fn part2(polymer: &str) -> usize {
-    ('a'..'z')
+    ('a'..='z')
         .map(|l| polymer.chars().filter(move |v| v.to_ascii_lowercase() != l))
         .map(|mut p| react(&mut p).len())
         .min()
@@ -45,7 +45,7 @@ fn part2(elf_foods: &[Option<i64>]) -> i64 {
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str = "
+    const SAMPLE: &str = "
 1000
 2000
 3000
@@ -1,14 +1,14 @@
 use std::str::FromStr;
 
-use aoc_rs::{get_input, reparse};
+use aoc_rs::{get_input, regexparser::ReparseError, reparse_lines};
 
 fn main() -> anyhow::Result<()> {
     let input = get_input(2022, 2)?;
 
-    let strategy1 = parse1(&input);
+    let strategy1 = parse1(&input)?;
     println!("part1: {}", part1(&strategy1));
 
-    let strategy2 = parse2(&input);
+    let strategy2 = parse2(&input)?;
     println!("part2: {}", part2(&strategy2));
 
     Ok(())
@@ -99,12 +99,8 @@ impl FromStr for GameResult {
     }
 }
 
-fn parse1(input: &str) -> Vec<(Play, Play)> {
-    input
-        .trim()
-        .split('\n')
-        .map(|line| reparse!((Play, Play), "(.) (.)", line))
-        .collect()
+fn parse1(input: &str) -> Result<Vec<(Play, Play)>, ReparseError> {
+    reparse_lines!((Play, Play), "(.) (.)", input, skip_blank)
 }
 
 fn value1(&(other, my): &(Play, Play)) -> i64 {
@@ -115,12 +111,8 @@ fn part1(strategy: &[(Play, Play)]) -> i64 {
     strategy.iter().map(value1).sum()
 }
 
-fn parse2(input: &str) -> Vec<(Play, GameResult)> {
-    input
-        .trim()
-        .split('\n')
-        .map(|line| reparse!((Play, GameResult), "(.) (.)", line))
-        .collect()
+fn parse2(input: &str) -> Result<Vec<(Play, GameResult)>, ReparseError> {
+    reparse_lines!((Play, GameResult), "(.) (.)", input, skip_blank)
 }
 
 fn value2(&(other, result): &(Play, GameResult)) -> i64 {
@@ -137,7 +129,7 @@ fn part2(strategy: &[(Play, GameResult)]) -> i64 {
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str = "
+    const SAMPLE: &str = "
 A Y
 B X
 C Z
@@ -145,13 +137,13 @@ C Z
 
     #[test]
     fn test_part1() {
-        let strategy = parse1(SAMPLE);
+        let strategy = parse1(SAMPLE).unwrap();
         assert_eq!(part1(&strategy), 15);
     }
 
     #[test]
     fn test_part2() {
-        let strategy = parse2(SAMPLE);
+        let strategy = parse2(SAMPLE).unwrap();
         assert_eq!(part2(&strategy), 12);
     }
 }
\ No newline at end of file
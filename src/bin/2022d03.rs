@@ -57,7 +57,7 @@ impl FromStr for Ruksack {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.chars().map(|c| Item::from_char(c)).collect::<Result<Vec<_>, ()>>()?))
+        Ok(Self(s.chars().map(Item::from_char).collect::<Result<Vec<_>, ()>>()?))
     }
 }
 
@@ -116,7 +116,7 @@ fn part2(ruksacks: &[Ruksack]) -> i64 {
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str = "
+    const SAMPLE: &str = "
 vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
@@ -1,10 +1,10 @@
-use std::ops::RangeInclusive;
+use aoc_rs::{get_input, interval::Interval, regexparser::ReparseError, reparse_lines};
 
-use aoc_rs::{get_input, reparse};
+type Pair = (Interval, Interval);
 
 fn main() -> anyhow::Result<()> {
     let input = get_input(2022, 4)?;
-    let pairs = parse(&input);
+    let pairs = parse(&input)?;
 
     println!("part1: {}", part1(&pairs));
     println!("part2: {}", part2(&pairs));
@@ -12,38 +12,25 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn parse(input: &str) -> Vec<(RangeInclusive<i64>, RangeInclusive<i64>)> {
-    input
-        .trim()
-        .split('\n')
-        .map(|v| reparse!((i64, i64, i64, i64), r"^(\d+)-(\d+),(\d+)-(\d+)$", v))
-        .map(|(a, b, c, d)| ((a..=b), (c..=d)))
-        .collect()
-}
-
-fn fully_contains(a: &RangeInclusive<i64>, b: &RangeInclusive<i64>) -> bool {
-    (a.contains(&b.start()) && a.contains(&b.end())) ||
-    (b.contains(&a.start()) && b.contains(&a.end()))
-}
+fn parse(input: &str) -> Result<Vec<Pair>, ReparseError> {
+    let pairs = reparse_lines!((i64, i64, i64, i64), r"^(\d+)-(\d+),(\d+)-(\d+)$", input, skip_blank)?;
 
-fn partially_contains(a: &RangeInclusive<i64>, b: &RangeInclusive<i64>) -> bool {
-    (a.contains(&b.start()) || a.contains(&b.end())) ||
-    (b.contains(&a.start()) || b.contains(&a.end()))
+    Ok(pairs.into_iter().map(|(a, b, c, d)| (Interval::new(a, b), Interval::new(c, d))).collect())
 }
 
-fn part1(pairs: &[(RangeInclusive<i64>, RangeInclusive<i64>)]) -> usize {
-    pairs.iter().filter(|(a, b)| fully_contains(a, b)).count()
+fn part1(pairs: &[Pair]) -> usize {
+    pairs.iter().filter(|(a, b)| a.contains_interval(*b) || b.contains_interval(*a)).count()
 }
 
-fn part2(pairs: &[(RangeInclusive<i64>, RangeInclusive<i64>)]) -> usize {
-    pairs.iter().filter(|(a, b)| partially_contains(a, b)).count()
+fn part2(pairs: &[Pair]) -> usize {
+    pairs.iter().filter(|(a, b)| a.overlaps(*b)).count()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str = "
+    const SAMPLE: &str = "
 2-4,6-8
 2-3,4-5
 5-7,7-9
@@ -54,13 +41,13 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let pairs = parse(SAMPLE);
+        let pairs = parse(SAMPLE).unwrap();
         assert_eq!(part1(&pairs), 2);
     }
 
     #[test]
     fn test_part2() {
-        let pairs = parse(SAMPLE);
+        let pairs = parse(SAMPLE).unwrap();
         assert_eq!(part2(&pairs), 4);
     }
 }
\ No newline at end of file
@@ -1,4 +1,11 @@
-use aoc_rs::{get_input, reparse};
+use aoc_rs::{get_input, regexparser::FromCaptures, reparse_into};
+
+#[derive(Debug, FromCaptures)]
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+}
 
 fn main() -> anyhow::Result<()> {
     let input = get_input(2022, 5)?;
@@ -10,7 +17,7 @@ fn main() -> anyhow::Result<()> {
     */
 
     #[allow(unused)]
-    let sample_piles = vec![
+    let sample_piles = [
         vec!['Z', 'N'],
         vec!['M', 'C', 'D'],
         vec!['P'],
@@ -33,7 +40,7 @@ fn main() -> anyhow::Result<()> {
 
     let moves = parse_moves(&input);
 
-    for (count, from, to) in moves {
+    for Move { count, from, to } in moves {
         for _ in 0..count {
             let v = piles[from - 1].pop().unwrap();
             piles[to - 1].push(v);
@@ -50,7 +57,7 @@ fn main() -> anyhow::Result<()> {
 
     let moves = parse_moves(&input);
 
-    for (count, from, to) in moves {
+    for Move { count, from, to } in moves {
         let fp = &mut piles[from - 1];
         let fi = fp.len() - count;
         let fv = fp.split_off(fi);
@@ -66,11 +73,11 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn parse_moves(input: &str) -> Vec<(usize, usize, usize)> {
+fn parse_moves(input: &str) -> Vec<Move> {
     input
         .trim()
         .split("\n")
         .skip_while(|line| !line.contains("move"))
-        .map(|line| reparse!((usize, usize, usize), r"move (\d+) from (\d+) to (\d+)", line))
+        .map(|line| reparse_into!(Move, r"move (?P<count>\d+) from (?P<from>\d+) to (?P<to>\d+)", line))
         .collect()
 }
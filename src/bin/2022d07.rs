@@ -61,7 +61,7 @@ fn parse(input: &str) -> DirectoryEntry {
     tree
 }
 
-fn parse_inner<'a>(input: &mut Vec<&str>) -> DirectoryEntry {
+fn parse_inner(input: &mut Vec<&str>) -> DirectoryEntry {
     let mut tree = DirectoryTree::new();
 
     while !input.is_empty() {
@@ -75,7 +75,7 @@ fn parse_inner<'a>(input: &mut Vec<&str>) -> DirectoryEntry {
             &["$", "cd", name] => { tree.insert(name.to_string(), parse_inner(input)); },
             &["dir", _] => {},
             &[size, name] => { tree.insert(name.to_string(), DirectoryEntry::File(size.parse().unwrap())); },
-            l @ _ => panic!("unknown line format {l:?}"),
+            l => panic!("unknown line format {l:?}"),
         }
     }
 
@@ -126,7 +126,7 @@ fn part2(tree: &DirectoryEntry) -> usize {
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str =
+    const SAMPLE: &str =
 "$ cd /
 $ ls
 dir a
@@ -154,13 +154,13 @@ $ ls
 
     #[test]
     fn test_part1() {
-        let tree = parse(&SAMPLE);
+        let tree = parse(SAMPLE);
         assert_eq!(part1(&tree), 95437);
     }
 
     #[test]
     fn test_part2() {
-        let tree = parse(&SAMPLE);
+        let tree = parse(SAMPLE);
         assert_eq!(part2(&tree), 24933642);
     }
 }
@@ -1,41 +1,23 @@
-use std::{str::FromStr, collections::HashSet};
-
-use aoc_rs::get_input;
+use aoc_rs::{get_input, ocr::{self, OcrError}, sparsegrid::SparseGrid, AocParse};
 
 fn main() -> anyhow::Result<()> {
     let input = get_input(2022, 10)?;
     let instructions = parse(&input);
 
     println!("part1: {}", part1(&instructions));
-
-    println!("sample 2:");
-    part2(&parse(SAMPLE));
-
-    println!("part2:");
-    part2(&instructions);
+    println!("part2: {}", part2(&instructions)?);
 
     Ok(())
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, AocParse)]
 enum Instruction {
+    #[aoc(format = "addx {0}")]
     Addx(i64),
+    #[aoc(format = "noop")]
     Noop,
 }
 
-impl FromStr for Instruction {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ');
-        match parts.next() {
-            Some("addx") => Ok(Self::Addx(parts.next().unwrap().parse().unwrap())),
-            Some("noop") => Ok(Self::Noop),
-            _ => Err(()),
-        }
-    }
-}
-
 fn parse(input: &str) -> Vec<Instruction> {
     input
         .trim()
@@ -75,34 +57,21 @@ fn part1(instructions: &[Instruction]) -> i64 {
 }
 
 fn position(cycle: i64) -> (i64, i64) {
-    (cycle / 40, cycle % 40)
+    (cycle % 40, cycle / 40)
 }
 
-fn print_screen(screen: HashSet<(i64, i64)>) {
-    for r in 0..6 {
-        for c in 0..40 {
-            if screen.contains(&(r, c)) {
-                print!("#");
-            } else {
-                print!(" ");
-            }
-        }
-        println!();
-    }
-}
-
-fn set_screen(cycle: i64, x: i64, screen: &mut HashSet<(i64, i64)>) {
+fn set_screen(cycle: i64, x: i64, screen: &mut SparseGrid<()>) {
     let sprite = (x-1)..=(x+1);
     let position = position(cycle);
-    if sprite.contains(&position.1) {
-        screen.insert(position);
+    if sprite.contains(&position.0) {
+        screen.insert(position, ());
     }
 }
 
-fn part2(instructions: &[Instruction]) {
+fn draw(instructions: &[Instruction]) -> SparseGrid<()> {
     let mut cycle = 0;
     let mut x = 1;
-    let mut screen = HashSet::new();
+    let mut screen = SparseGrid::new();
 
     instructions.iter().for_each(|instr| {
         match instr {
@@ -120,11 +89,20 @@ fn part2(instructions: &[Instruction]) {
         }
     });
 
-    print_screen(screen);
+    screen
 }
 
+fn part2(instructions: &[Instruction]) -> Result<String, OcrError> {
+    ocr::recognize_points(draw(instructions).positions().map(|&p| p.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_rs::sparsegrid::YAxis;
+
+    use super::*;
 
-const SAMPLE: &'static str = "addx 15
+    const SAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -271,13 +249,59 @@ noop
 noop
 noop";
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[test]
     fn test_part1() {
-        let instructions = parse(&SAMPLE);
+        let instructions = parse(SAMPLE);
         assert_eq!(part1(&instructions), 13140);
     }
+
+    /// A program that draws `screen`, holding X steady over each `addx` so
+    /// it lights exactly the pixels wanted in those two cycles.
+    fn program(screen: &str) -> Vec<Instruction> {
+        let mut xs = vec![];
+        for row in screen.lines() {
+            let row = row.as_bytes();
+            for c in (0..row.len()).step_by(2) {
+                xs.push(match (row[c] == b'#', row[c + 1] == b'#') {
+                    (true, true) => c as i64,
+                    (true, false) => c as i64 - 1,
+                    (false, true) => c as i64 + 2,
+                    (false, false) => c as i64 + 3,
+                });
+            }
+        }
+
+        // X starts at 1, which lights the first two pixels
+        assert_eq!(&screen[..2], "##");
+        xs[0] = 1;
+        xs.push(xs[xs.len() - 1]);
+        xs.windows(2).map(|w| Instruction::Addx(w[1] - w[0])).collect()
+    }
+
+    #[test]
+    fn test_part2() {
+        let screen = "\
+####.#..#.###..####.###....##..##..#....
+#....#..#.#..#....#.#..#....#.#..#.#....
+###..####.#..#...#..#..#....#.#....#....
+#....#..#.###...#...###.....#.#.##.#....
+#....#..#.#....#....#....#..#.#..#.#....
+####.#..#.#....####.#.....##...###.####.";
+        assert_eq!(part2(&program(screen)), Ok("EHPZPJGL".into()));
+        assert!(matches!(part2(&parse(SAMPLE)), Err(OcrError::UnknownGlyphs { .. })));
+    }
+
+    #[test]
+    fn test_draw() {
+        let screen = draw(&parse(SAMPLE));
+        let picture = screen.render_region(0..=39, 0..=5, YAxis::Down, |c| if c.is_some() { '#' } else { '.' });
+        assert_eq!(picture, "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+");
+    }
 }
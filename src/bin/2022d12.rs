@@ -0,0 +1,75 @@
+use aoc_rs::{get_input, grid::Grid, search::bfs};
+
+fn main() -> anyhow::Result<()> {
+    let input = get_input(2022, 12)?;
+    let map = parse(&input);
+
+    println!("part1: {}", part1(&map));
+    println!("part2: {}", part2(&map));
+
+    Ok(())
+}
+
+struct Map {
+    heights: Grid<u8>,
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+fn parse(input: &str) -> Map {
+    let letters = Grid::parse(input, |c| c.is_ascii_alphabetic().then_some(c as u8)).expect("Malformed input");
+    let find = |c| letters.positions().find(|&p| letters[p] == c).expect("Missing start or end");
+    let (start, end) = (find(b'S'), find(b'E'));
+
+    let heights = letters.map(|&c| match c {
+        b'S' => b'a',
+        b'E' => b'z',
+        c => c,
+    });
+
+    Map { heights, start, end }
+}
+
+fn climb(map: &Map, starts: impl IntoIterator<Item = (usize, usize)>) -> usize {
+    let uphill = |&p: &(usize, usize)| {
+        let limit = map.heights[p] + 1;
+        map.heights.neighbors4(p).filter(move |&q| map.heights[q] <= limit)
+    };
+
+    bfs(starts, uphill, |&p| p == map.end)
+        .goal_distance()
+        .expect("No path to the top")
+}
+
+fn part1(map: &Map) -> usize {
+    climb(map, [map.start])
+}
+
+fn part2(map: &Map) -> usize {
+    let lowest = map.heights.positions().filter(|&p| map.heights[p] == b'a');
+    climb(map, lowest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    #[test]
+    fn test_part1() {
+        let map = parse(SAMPLE);
+        assert_eq!(part1(&map), 31);
+    }
+
+    #[test]
+    fn test_part2() {
+        let map = parse(SAMPLE);
+        assert_eq!(part2(&map), 29);
+    }
+}
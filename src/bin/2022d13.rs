@@ -0,0 +1,91 @@
+use aoc_rs::{get_input, parser::Parser, value::Value};
+
+fn main() -> anyhow::Result<()> {
+    let input = get_input(2022, 13)?;
+    let pairs = parse(&input);
+
+    println!("part1: {}", part1(&pairs));
+    println!("part2: {}", part2(&pairs));
+
+    Ok(())
+}
+
+fn parse(input: &str) -> Vec<(Value, Value)> {
+    let mut parser = Parser::new(input.trim());
+    let pairs = parser.sep_by(|p| {
+        let left = p.try_value()?;
+        p.eol()?;
+        let right = p.try_value()?;
+        Ok((left, right))
+    }, "\n\n");
+    parser.expect_done().expect("Malformed input");
+    pairs
+}
+
+fn part1(pairs: &[(Value, Value)]) -> usize {
+    pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
+        .sum()
+}
+
+fn part2(pairs: &[(Value, Value)]) -> usize {
+    let dividers: [Value; 2] = ["[[2]]".parse().unwrap(), "[[6]]".parse().unwrap()];
+
+    let mut packets = pairs
+        .iter()
+        .flat_map(|(left, right)| [left, right])
+        .chain(&dividers)
+        .collect::<Vec<_>>();
+    packets.sort();
+
+    dividers
+        .iter()
+        .map(|d| packets.iter().position(|&p| std::ptr::eq(p, d)).unwrap() + 1)
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    #[test]
+    fn test_part1() {
+        let pairs = parse(SAMPLE);
+        assert_eq!(part1(&pairs), 13);
+    }
+
+    #[test]
+    fn test_part2() {
+        let pairs = parse(SAMPLE);
+        assert_eq!(part2(&pairs), 140);
+    }
+}
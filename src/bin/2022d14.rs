@@ -1,5 +1,4 @@
-use std::collections::HashMap;
-use aoc_rs::get_input;
+use aoc_rs::{get_input, reparse_all, sparsegrid::{SparseGrid, YAxis}};
 use itertools::Itertools;
 
 fn main() -> anyhow::Result<()> {
@@ -13,33 +12,13 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn parse(input: &str) -> HashMap<(i32, i32), u8> {
-   let iter = input 
-        .trim()
-        .split("\n")
-        .map(|line| {
-            line
-                .split(" -> ")
-                .map(|entry| entry
-                        .split(",")
-                        .map(|n| n
-                            .parse::<i32>()
-                            .unwrap()
-                        )
-                    )
-        });
-    
-    let mut field = HashMap::new();
-
-    for line in iter {
-        for (mut from, mut to) in line.tuple_windows() {
-            let x1 = from.next().unwrap();
-            let y1 = from.next().unwrap();
-            let x2 = to.next().unwrap();
-            let y2 = to.next().unwrap();
-
-            // println!("{x1}-{x2} {y1}-{y2}");
-            
+fn parse(input: &str) -> SparseGrid<u8> {
+    let mut field = SparseGrid::new();
+
+    for line in input.trim().split('\n') {
+        let path = reparse_all!((i64, i64), r"(\d+),(\d+)", line);
+
+        for ((x1, y1), (x2, y2)) in path.tuple_windows() {
             for x in x1..=x2 {
                 for y in y1..=y2 {
                     field.insert((x, y), b'#');
@@ -56,46 +35,42 @@ fn parse(input: &str) -> HashMap<(i32, i32), u8> {
     field
 }
 
-fn modify_for_part_2(field: &mut HashMap<(i32, i32), u8>) {
-    let highest_position = field.keys().max_by_key(|(_x, y)| y).unwrap().1;
+fn modify_for_part_2(field: &mut SparseGrid<u8>) {
+    let highest_position = field.max_y().unwrap();
     for i in -500..1500 {
         field.insert((i, highest_position + 2), b'#');
     }
 }
 
-fn print_sample(field: &HashMap<(i32, i32), u8>) {
-    for y in 0..=9 {
-        for x in 494..=503 {
-            print!("{}", *field.get(&(x, y)).unwrap_or(&b'.') as char)
-        }
-        println!();
-    }
+#[allow(unused)]
+fn print_sample(field: &SparseGrid<u8>) {
+    print!("{}", field.render(YAxis::Down, |c| c.map_or('.', |&b| b as char)));
 }
 
-fn place_one_grain(field: &mut HashMap<(i32, i32), u8>) -> Result<(i32, i32), ()> {
+fn place_one_grain(field: &mut SparseGrid<u8>) -> Result<(i64, i64), ()> {
     let mut place = (500, 0);
-    if field.contains_key(&place) {
+    if field.contains(place) {
         return Err(());
     }
-    let max_y = field.keys().max_by_key(|(_x, y)| y).unwrap().1;
+    let max_y = field.max_y().unwrap();
 
     loop {
         if place.1 > max_y {
             return Err(());
         }
 
-        if !field.contains_key(&(place.0, place.1 + 1)) {
+        if !field.contains((place.0, place.1 + 1)) {
             place.1 += 1;
             continue;
         }
 
-        if !field.contains_key(&(place.0 - 1, place.1 + 1)) {
+        if !field.contains((place.0 - 1, place.1 + 1)) {
             place.0 -= 1;
             place.1 += 1;
             continue;
         }
 
-        if !field.contains_key(&(place.0 + 1, place.1 + 1)) {
+        if !field.contains((place.0 + 1, place.1 + 1)) {
             place.0 += 1;
             place.1 += 1;
             continue;
@@ -106,7 +81,7 @@ fn place_one_grain(field: &mut HashMap<(i32, i32), u8>) -> Result<(i32, i32), ()
     }
 }
 
-fn part1(mut field: HashMap<(i32, i32), u8>) -> usize {
+fn part1(mut field: SparseGrid<u8>) -> usize {
     while place_one_grain(&mut field).is_ok() {
         // print_sample(&field);
     }
@@ -118,7 +93,7 @@ fn part1(mut field: HashMap<(i32, i32), u8>) -> usize {
 mod tests {
     use super::*;
 
-    const SAMPLE: &'static str = "498,4 -> 498,6 -> 496,6
+    const SAMPLE: &str = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
 
     #[test]
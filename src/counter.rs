@@ -0,0 +1,197 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
+
+/// A multiset counting how many times each item was seen. Iteration is in
+/// item order, so results don't depend on hashing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Counter<T> {
+    counts: BTreeMap<T, usize>,
+}
+
+impl<T> Default for Counter<T> {
+    fn default() -> Self {
+        Self { counts: BTreeMap::new() }
+    }
+}
+
+impl<T: Ord> Counter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, item: T) {
+        self.insert_n(item, 1);
+    }
+
+    pub fn insert_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+        }
+    }
+
+    /// Takes up to `n` off the count of `item`, forgetting it at zero.
+    pub fn remove_n(&mut self, item: &T, n: usize) {
+        if let Some(count) = self.counts.get_mut(item) {
+            *count = count.saturating_sub(n);
+            if *count == 0 {
+                self.counts.remove(item);
+            }
+        }
+    }
+
+    /// How many times `item` was counted, zero if never.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all counts.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    pub fn keys(&self) -> btree_map::Keys<'_, T, usize> {
+        self.counts.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = usize> + '_ {
+        self.counts.values().copied()
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&T, usize) -> bool) {
+        self.counts.retain(|item, count| f(item, *count));
+    }
+
+    /// The `n` most counted items, most first. Ties go to the smaller item.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, usize)> {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        items.truncate(n);
+        items
+    }
+
+    /// The most counted item, the smallest one on a tie.
+    pub fn max_by_count(&self) -> Option<(&T, usize)> {
+        self.iter().reduce(|best, next| if next.1 > best.1 { next } else { best })
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Ord> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T> IntoIterator for Counter<T> {
+    type Item = (T, usize);
+    type IntoIter = btree_map::IntoIter<T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<T: Ord> AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Self) {
+        for (item, count) in other {
+            self.insert_n(item, count);
+        }
+    }
+}
+
+impl<T: Ord> Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+/// Subtracts counts, dropping items that reach zero.
+impl<T: Ord> SubAssign for Counter<T> {
+    fn sub_assign(&mut self, other: Self) {
+        for (item, count) in other {
+            self.remove_n(&item, count);
+        }
+    }
+}
+
+impl<T: Ord> Sub for Counter<T> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+        assert_eq!(counter.keys().collect::<String>(), "abcdr");
+        assert_eq!(counter.values().collect::<Vec<_>>(), [5, 2, 1, 1, 2]);
+        assert!(counter.values().any(|v| v == 2));
+    }
+
+    #[test]
+    fn test_most_common() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.most_common(3), [(&'a', 5), (&'b', 2), (&'r', 2)]);
+        assert_eq!(counter.most_common(10).len(), 5);
+        assert_eq!(counter.max_by_count(), Some((&'a', 5)));
+
+        let tied: Counter<u8> = [3, 1, 3, 1].into_iter().collect();
+        assert_eq!(tied.max_by_count(), Some((&1, 2)));
+        assert_eq!(Counter::<u8>::new().max_by_count(), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Counter<char> = "aab".chars().collect();
+        let b: Counter<char> = "abbc".chars().collect();
+
+        let sum = a.clone() + b.clone();
+        assert_eq!(sum.iter().collect::<Vec<_>>(), [(&'a', 3), (&'b', 3), (&'c', 1)]);
+
+        let difference = a - b;
+        assert_eq!(difference.iter().collect::<Vec<_>>(), [(&'a', 1)]);
+
+        let mut c = sum;
+        c.retain(|_, count| count > 1);
+        c.remove_n(&'b', 5);
+        c.insert_n('z', 0);
+        assert_eq!(c.into_iter().collect::<Vec<_>>(), [('a', 3)]);
+    }
+}
@@ -0,0 +1,188 @@
+//! Finding where repeatedly applying a step function starts to loop, for
+//! simulations that must be run for far more steps than is feasible.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// The state after `start` steps is the first one that comes round again,
+/// `length` steps later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub length: usize,
+    pub state: S,
+}
+
+impl<S> Cycle<S> {
+    /// The earliest step whose state is the same as after `n` steps.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare: constant memory, but steps every state about
+/// three times over.
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length, state: tortoise }
+}
+
+/// Brent's algorithm: constant memory like [`floyd`], with fewer steps.
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length, state: tortoise }
+}
+
+/// Remembers every state seen, stepping each just once.
+pub fn find_cycle<S: Clone + Eq + Hash>(initial: S, step: impl FnMut(&S) -> S) -> Cycle<S> {
+    find_cycle_by_key(initial, step, S::clone)
+}
+
+/// Like [`find_cycle`], but treats two states as the same when their keys
+/// are, for states too big to remember whole or that carry a counter that
+/// never repeats. The returned `state` is the later of the two, the one
+/// after `start + length` steps.
+pub fn find_cycle_by_key<S, K: Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle<S> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(start) = seen.insert(key(&state), i) {
+            return Cycle { start, length: i - start, state };
+        }
+        state = step(&state);
+    }
+    unreachable!()
+}
+
+/// The first item equal to one before it, or `None` if `items` runs out
+/// first. Loops forever on an endless iterator that never repeats.
+pub fn first_repeat<T: Clone + Eq + Hash>(items: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut seen = HashSet::new();
+    items.into_iter().find(|item| !seen.insert(item.clone()))
+}
+
+/// The state after `n` steps, skipping ahead once the states start to loop.
+pub fn extrapolate<S: Clone + Eq + Hash>(mut step: impl FnMut(&S) -> S, state: S, n: usize) -> S {
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut state = state;
+
+    for i in 0..n {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            let cycle = Cycle { start, length: i - start, state: () };
+            return history.swap_remove(cycle.equivalent_step(n));
+        }
+        history.push(state.clone());
+        state = step(&state);
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn step(&x: &u32) -> u32 {
+        if x == 5 { 2 } else { x + 1 }
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let expected = Cycle { start: 2, length: 4, state: 2 };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(find_cycle(0, step), expected);
+
+        // a pure loop starts straight away
+        assert_eq!(brent(3, step), Cycle { start: 0, length: 4, state: 3 });
+        assert_eq!(floyd(7, |&x| (x + 1) % 10), Cycle { start: 0, length: 10, state: 7 });
+        assert_eq!(find_cycle(1, |&x| x), Cycle { start: 0, length: 1, state: 1 });
+    }
+
+    #[test]
+    fn test_by_key() {
+        // the counter never repeats, the position does
+        let cycle = find_cycle_by_key((0u64, 0u32), |&(t, x)| (t + 1, step(&x)), |&(_, x)| x);
+        assert_eq!((cycle.start, cycle.length), (2, 4));
+        assert_eq!(cycle.state, (6, 2));
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(1_000_000_000_000), 4);
+    }
+
+    #[test]
+    fn test_first_repeat() {
+        assert_eq!(first_repeat([3, 1, 4, 1, 5, 3]), Some(1));
+        assert_eq!(first_repeat("abc".chars()), None);
+        assert_eq!(first_repeat(std::iter::successors(Some(0), |x| Some(step(x)))), Some(2));
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        for n in 0..20 {
+            let slow = (0..n).fold(0, |x, _| step(&x));
+            assert_eq!(extrapolate(step, 0, n), slow, "after {n} steps");
+        }
+        assert_eq!(extrapolate(step, 0, 1_000_000_000_000), 4);
+    }
+}
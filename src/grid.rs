@@ -0,0 +1,359 @@
+use std::{fmt, ops::{Index, IndexMut}};
+
+use crate::{parser::{ParseError, Parser}, point::Point2};
+
+/// Up, right, down and left, as `(dx, dy)` steps.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The orthogonal steps plus the four diagonals, clockwise from up.
+pub const ADJACENT: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// A rectangular grid stored row-major, indexed by `(x, y)` with `y`
+/// counting rows down from the top.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "a {width}x{height} grid needs {} cells", width * height);
+        Self { width, height, cells }
+    }
+
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_vec(width, height, vec![fill; width * height])
+    }
+
+    /// Parses text one character per cell, see [`Parser::grid`].
+    pub fn parse(input: &str, map: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input.trim_end_matches(['\n', '\r']));
+        let grid = parser.grid(map)?;
+        parser.expect_done()?;
+        Ok(grid)
+    }
+
+    /// Like [`Grid::parse`], but short rows are padded out with `fill`, see
+    /// [`Parser::grid_padded`].
+    pub fn parse_padded(input: &str, fill: T, map: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError>
+    where
+        T: Clone,
+    {
+        let mut parser = Parser::new(input.trim_end_matches(['\n', '\r']));
+        let grid = parser.grid_padded(fill, map)?;
+        parser.expect_done()?;
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn offset(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.offset(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.offset(x, y).map(|i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} is outside a {}x{} grid", self.width, self.height);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside a {}x{} grid", self.width, self.height);
+        // with no rows there are no cells to start from at x
+        self.cells.get(x..).unwrap_or_default().iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Every `(x, y)` in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// `(x, y)` moved by `(dx, dy)`, if that's still inside the grid.
+    pub fn step(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    pub fn neighbors4(&self, at: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL.into_iter().filter_map(move |d| self.step(at, d))
+    }
+
+    pub fn neighbors8(&self, at: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ADJACENT.into_iter().filter_map(move |d| self.step(at, d))
+    }
+
+    /// The positions reached by repeatedly stepping `direction` from `from`,
+    /// not including `from` itself, up to the edge of the grid.
+    pub fn ray(&self, from: (usize, usize), direction: (isize, isize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        assert_ne!(direction, (0, 0), "a ray needs a direction");
+        std::iter::successors(self.step(from, direction), move |&at| self.step(at, direction))
+    }
+
+    /// Builds a `width` by `height` grid taking each cell from `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect();
+        Self::from_vec(width, height, cells)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Mirrors the grid along its main diagonal, so `(x, y)` moves to `(y, x)`.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, self.height - 1 - x)].clone())
+    }
+
+    /// Rotates a quarter turn anticlockwise.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.height, self.width, |x, y| self[(self.width - 1 - y, x)].clone())
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.width, self.height, |x, y| self[(self.width - 1 - x, y)].clone())
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.width, self.height, |x, y| self[(x, self.height - 1 - y)].clone())
+    }
+}
+
+/// Writes each row of cells on its own line, with nothing between cells.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside a {width}x{height} grid"))
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2<usize>) -> &T {
+        &self[(p.x, p.y)]
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, p: Point2<usize>) -> &mut T {
+        &mut self[(p.x, p.y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParseErrorKind;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Tile {
+        Open,
+        Wall,
+        Void,
+    }
+
+    fn tile(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Open),
+            '#' => Some(Tile::Wall),
+            ' ' => Some(Tile::Void),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_input_grid() {
+        let input = crate::Input::from("#.\n.#\n\n");
+        let grid = input.grid(tile).unwrap();
+        assert_eq!(grid.row(1), &[Tile::Open, Tile::Wall]);
+
+        let input = crate::Input::from("#.\n#\n.#.\n");
+        assert!(matches!(input.grid(tile).unwrap_err().kind, ParseErrorKind::RaggedRow { line: 2, .. }));
+        let grid = input.grid_padded(Tile::Void, tile).unwrap();
+        assert_eq!(grid.row(1), &[Tile::Wall, Tile::Void, Tile::Void]);
+    }
+
+    #[test]
+    fn test_parse_digits() {
+        let grid = Grid::parse("303\n255\n", |c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 1)], 2);
+        assert_eq!(grid.row(1), &[2, 5, 5]);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+
+        let grid = Grid::parse_padded("3\n255\n", 0, |c| c.to_digit(10)).unwrap();
+        assert_eq!(grid.row(0), &[3, 0, 0]);
+    }
+
+    #[test]
+    fn test_ragged() {
+        let error = Grid::parse("...\n..\n...", tile).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::RaggedRow { line: 2, width: 2, expected: 3 });
+
+        let error = Grid::parse("...\n.x.", tile).unwrap_err();
+        assert_eq!(error.at, 5);
+    }
+
+    #[test]
+    fn test_trailing_spaces() {
+        let grid = Grid::parse("#.#\n.  \r\n", tile).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.row(1), &[Tile::Open, Tile::Void, Tile::Void]);
+    }
+
+    #[test]
+    fn test_padded() {
+        let mut parser = Parser::new("  ..#\n .#\n#\n\n10R5");
+        let grid = parser.grid_padded(Tile::Void, tile).unwrap();
+        assert_eq!((grid.width(), grid.height()), (5, 3));
+        assert_eq!(grid.row(1), &[Tile::Void, Tile::Open, Tile::Wall, Tile::Void, Tile::Void]);
+        assert_eq!(grid[(0, 2)], Tile::Wall);
+
+        assert!(parser.eol().is_ok());
+        assert_eq!(parser.rest(), "10R5");
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut grid = Grid::new(2, 2, 0);
+        grid[(1, 0)] = 5;
+        *grid.get_mut(0, 1).unwrap() = 3;
+        grid[Point2::new(1, 1)] += 1;
+        assert_eq!(grid.cells(), &[0, 5, 3, 1]);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::parse("123\n456", |c| c.to_digit(10)).unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.columns().map(|c| c.sum::<u32>()).collect::<Vec<_>>(), [5, 7, 9]);
+        assert_eq!(grid.positions().collect::<Vec<_>>(), [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+
+        let empty: Grid<u8> = Grid::from_vec(0, 0, vec![]);
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.to_string(), "");
+
+        let narrow: Grid<u8> = Grid::from_vec(0, 3, vec![]);
+        assert_eq!(narrow.rows().collect::<Vec<_>>(), [&[]; 3]);
+
+        let flat: Grid<u8> = Grid::from_vec(3, 0, vec![]);
+        assert_eq!(flat.column(2).count(), 0);
+        assert_eq!(flat.columns().map(Iterator::count).collect::<Vec<_>>(), [0, 0, 0]);
+        assert_eq!(flat.rows().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "row 99 is outside a 0x3 grid")]
+    fn test_row_out_of_range() {
+        Grid::<u8>::from_vec(0, 3, vec![]).row(99);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((2, 2)).collect::<Vec<_>>(), [(2, 1), (1, 2), (1, 1)]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::new(4, 3, ());
+        assert_eq!(grid.ray((1, 1), (1, 0)).collect::<Vec<_>>(), [(2, 1), (3, 1)]);
+        assert_eq!(grid.ray((1, 1), (0, -1)).collect::<Vec<_>>(), [(1, 0)]);
+        assert_eq!(grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(), [(1, 1), (2, 2)]);
+        assert_eq!(grid.ray((0, 0), (-1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = Grid::parse("ab\ncd\nef", Some).unwrap();
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf\n");
+        assert_eq!(grid.rotate_right().to_string(), "eca\nfdb\n");
+        assert_eq!(grid.rotate_left().to_string(), "bdf\nace\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe\n");
+        assert_eq!(grid.flip_vertical().to_string(), "ef\ncd\nab\n");
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+        assert_eq!(grid.map(|c| c.to_ascii_uppercase()).row(2), &['E', 'F']);
+    }
+}
@@ -0,0 +1,226 @@
+//! Hexagonal grids in axial coordinates `(q, r)`; the implied third cube
+//! coordinate is `s = -q - r`. The same coordinates serve both hex layouts,
+//! only the direction names differ: [`Pointy`] for rows of pointy-topped
+//! hexes (e, se, sw, w, nw, ne) and [`Flat`] for columns of flat-topped ones
+//! (n, ne, se, s, sw, nw).
+
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use crate::parser::{ParseError, ParseErrorKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    /// From cube coordinates, which must sum to zero.
+    pub fn from_cube(q: i64, r: i64, s: i64) -> Self {
+        assert_eq!(q + r + s, 0, "cube coordinates ({q}, {r}, {s}) don't sum to zero");
+        Self { q, r }
+    }
+
+    pub fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (i64, i64, i64) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(self, other: Hex) -> i64 {
+        let d = self - other;
+        d.q.abs().max(d.r.abs()).max(d.s().abs())
+    }
+
+    pub fn step(self, direction: impl Into<Hex>) -> Hex {
+        self + direction.into()
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        Pointy::ALL.into_iter().map(move |d| self.step(d))
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<i64> for Hex {
+    type Output = Hex;
+
+    fn mul(self, k: i64) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, other: Hex) {
+        self.q += other.q;
+        self.r += other.r;
+    }
+}
+
+fn invalid_direction(at: usize, s: &str) -> ParseError {
+    ParseError::new(at, ParseErrorKind::Invalid { expected: "hex direction".to_owned(), text: s.to_owned() })
+}
+
+macro_rules! hex_directions {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $text:literal ($q:literal, $r:literal)),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl $name {
+            /// Clockwise, starting from the first direction.
+            pub const ALL: [$name; 6] = [$($name::$variant),*];
+
+            pub fn turn_right(self) -> Self {
+                Self::ALL[(self as usize + 1) % 6]
+            }
+
+            pub fn turn_left(self) -> Self {
+                Self::ALL[(self as usize + 5) % 6]
+            }
+
+            pub fn opposite(self) -> Self {
+                Self::ALL[(self as usize + 3) % 6]
+            }
+        }
+
+        impl From<$name> for Hex {
+            fn from(direction: $name) -> Hex {
+                match direction {
+                    $($name::$variant => Hex::new($q, $r)),*
+                }
+            }
+        }
+
+        /// Parses the lowercase or uppercase compass name.
+        impl FromStr for $name {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.to_ascii_lowercase().as_str() {
+                    $($text => Ok($name::$variant),)*
+                    _ => Err(invalid_direction(0, s)),
+                }
+            }
+        }
+    };
+}
+
+hex_directions!(
+    /// Directions between pointy-topped hexes laid out in rows.
+    Pointy {
+        E = "e" (1, 0),
+        SE = "se" (0, 1),
+        SW = "sw" (-1, 1),
+        W = "w" (-1, 0),
+        NW = "nw" (0, -1),
+        NE = "ne" (1, -1),
+    }
+);
+
+hex_directions!(
+    /// Directions between flat-topped hexes laid out in columns.
+    Flat {
+        N = "n" (0, -1),
+        NE = "ne" (1, -1),
+        SE = "se" (1, 0),
+        S = "s" (0, 1),
+        SW = "sw" (-1, 1),
+        NW = "nw" (-1, 0),
+    }
+);
+
+impl Pointy {
+    /// Splits an unseparated path like `"esenee"` into its directions.
+    pub fn parse_path(path: &str) -> Result<Vec<Pointy>, ParseError> {
+        let mut directions = vec![];
+        let mut rest = path;
+
+        while !rest.is_empty() {
+            let len = if rest.starts_with(['n', 's', 'N', 'S']) { 2 } else { 1 };
+            let at = path.len() - rest.len();
+            let text = rest.get(..len).ok_or_else(|| invalid_direction(at, rest))?;
+            let direction = text.parse().map_err(|_| invalid_direction(at, text))?;
+            directions.push(direction);
+            rest = &rest[len..];
+        }
+
+        Ok(directions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let origin = Hex::default();
+        assert_eq!(Hex::new(3, -1).distance(origin), 3);
+        assert_eq!(Hex::from_cube(-2, 3, -1).distance(Hex::new(1, 1)), 3);
+        assert_eq!(origin.neighbors().filter(|h| h.distance(origin) == 1).count(), 6);
+        assert_eq!(Hex::new(2, -5).cube(), (2, -5, 3));
+    }
+
+    #[test]
+    fn test_directions() {
+        for directions in [Pointy::ALL.map(Hex::from), Flat::ALL.map(Hex::from)] {
+            assert_eq!(directions.iter().fold(Hex::default(), |a, &b| a + b), Hex::default());
+        }
+        assert_eq!(Pointy::E.turn_right(), Pointy::SE);
+        assert_eq!(Pointy::E.turn_left(), Pointy::NE);
+        assert_eq!(Flat::N.opposite(), Flat::S);
+        assert_eq!(Hex::from(Flat::SE), -Hex::from(Flat::NW));
+
+        // 2017 day 11: ne,ne,s,s is two steps away
+        let path = "ne,ne,s,s".split(',').map(|d| d.parse::<Flat>().unwrap());
+        assert_eq!(path.fold(Hex::default(), Hex::step).distance(Hex::default()), 2);
+    }
+
+    #[test]
+    fn test_parse_path() {
+        // 2020 day 24: nwwswee flips the reference tile
+        let path = Pointy::parse_path("nwwswee").unwrap();
+        assert_eq!(path, [Pointy::NW, Pointy::W, Pointy::SW, Pointy::E, Pointy::E]);
+        assert_eq!(path.into_iter().fold(Hex::default(), Hex::step), Hex::default());
+
+        assert_eq!(Pointy::parse_path("eex").unwrap_err().at, 2);
+        assert_eq!(Pointy::parse_path("een").unwrap_err().at, 2);
+        assert_eq!(Pointy::parse_path("eeNx").unwrap_err().at, 2);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{fmt, ops::RangeInclusive};
+
+/// A non-empty inclusive range of integers, `start..=end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        assert!(start <= end, "interval {start}..={end} is empty");
+        Self { start, end }
+    }
+
+    /// The number of integers covered. Panics on `i64::MIN..=i64::MAX`,
+    /// whose 2^64 integers don't fit in a `u64`.
+    #[allow(clippy::len_without_is_empty)] // intervals are never empty
+    pub fn len(self) -> u64 {
+        let len = self.end.abs_diff(self.start).checked_add(1);
+        len.unwrap_or_else(|| panic!("interval {self} is too long to count"))
+    }
+
+    pub fn contains(self, x: i64) -> bool {
+        self.start <= x && x <= self.end
+    }
+
+    /// Whether `other` lies entirely within this interval.
+    pub fn contains_interval(self, other: Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(self, other: Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(self, other: Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Whether the two can be merged into one interval without covering
+    /// anything new: they overlap or sit right next to each other.
+    fn joins(self, other: Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+}
+
+impl From<RangeInclusive<i64>> for Interval {
+    fn from(range: RangeInclusive<i64>) -> Self {
+        Interval::new(*range.start(), *range.end())
+    }
+}
+
+impl From<Interval> for RangeInclusive<i64> {
+    fn from(interval: Interval) -> Self {
+        interval.start..=interval.end
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// A set of integers stored as sorted, disjoint intervals, with touching
+/// intervals merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers covered. Panics when that is all of `i64`,
+    /// like [`Interval::len`].
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .try_fold(0u64, |total, i| total.checked_add(i.end.abs_diff(i.start))?.checked_add(1))
+            .expect("interval set covers too many integers to count")
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        // the first interval ending at or after x is the only candidate
+        let i = self.intervals.partition_point(|i| i.end < x);
+        self.intervals.get(i).is_some_and(|i| i.contains(x))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let first = self.intervals.partition_point(|i| !i.joins(interval) && i.end < interval.start);
+        let last = first + self.intervals[first..].iter().take_while(|i| i.joins(interval)).count();
+
+        let merged = self.intervals[first..last].iter().fold(interval, |a, b| {
+            Interval::new(a.start.min(b.start), a.end.max(b.end))
+        });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        let mut kept = Vec::with_capacity(self.intervals.len() + 1);
+
+        for &i in &self.intervals {
+            if !i.overlaps(interval) {
+                kept.push(i);
+                continue;
+            }
+            if i.start < interval.start {
+                kept.push(Interval::new(i.start, interval.start - 1));
+            }
+            if interval.end < i.end {
+                kept.push(Interval::new(interval.end + 1, i.end));
+            }
+        }
+
+        self.intervals = kept;
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = self.clone();
+        other.intervals.iter().for_each(|&i| out.insert(i));
+        out
+    }
+
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        let mut out = self.clone();
+        other.intervals.iter().for_each(|&i| out.remove(i));
+        out
+    }
+
+    /// The parts of `within` that this set doesn't cover.
+    pub fn gaps(&self, within: Interval) -> IntervalSet {
+        let mut out = IntervalSet::new();
+        out.insert(within);
+        out.subtract(self)
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        iter.into_iter().for_each(|i| set.insert(i));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals.iter().map(|&(a, b)| Interval::new(a, b)).collect()
+    }
+
+    fn pairs(set: &IntervalSet) -> Vec<(i64, i64)> {
+        set.intervals().iter().map(|i| (i.start, i.end)).collect()
+    }
+
+    #[test]
+    fn test_predicates() {
+        let a = Interval::new(2, 8);
+        let b = Interval::from(3..=7);
+        let c = Interval::new(6, 10);
+
+        assert!(a.contains_interval(b) && !b.contains_interval(a));
+        assert!(a.overlaps(c) && !b.overlaps(Interval::new(8, 9)));
+        assert_eq!(a.intersection(c), Some(Interval::new(6, 8)));
+        assert_eq!(b.intersection(Interval::new(9, 9)), None);
+        assert_eq!(a.len(), 7);
+        assert!(a.contains(8) && !a.contains(9));
+        assert_eq!(a.to_string(), "2..=8");
+        assert_eq!(Interval::new(i64::MIN, i64::MAX - 1).len(), u64::MAX);
+        assert_eq!(set(&[(i64::MIN, -1), (1, i64::MAX)]).len(), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "too long to count")]
+    fn test_len_of_everything() {
+        Interval::new(i64::MIN, i64::MAX).len();
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let s = set(&[(5, 8), (0, 2), (10, 12)]);
+        assert_eq!(pairs(&s), [(0, 2), (5, 8), (10, 12)]);
+
+        // touching intervals merge too
+        assert_eq!(pairs(&set(&[(0, 2), (3, 4)])), [(0, 4)]);
+        assert_eq!(pairs(&set(&[(5, 8), (0, 2), (10, 12), (1, 10)])), [(0, 12)]);
+        assert_eq!(pairs(&set(&[(5, 8), (0, 2), (10, 12), (4, 4)])), [(0, 2), (4, 8), (10, 12)]);
+        assert_eq!(s.len(), 10);
+        assert!(s.contains(6) && !s.contains(9) && !s.contains(13));
+    }
+
+    #[test]
+    fn test_subtract_and_gaps() {
+        let s = set(&[(0, 10), (20, 30)]);
+        assert_eq!(pairs(&s.subtract(&set(&[(5, 22), (30, 40)]))), [(0, 4), (23, 29)]);
+        assert_eq!(pairs(&s.subtract(&set(&[(-5, 40)]))), []);
+        assert_eq!(pairs(&s.union(&set(&[(11, 19)]))), [(0, 30)]);
+
+        // 2016 day 20's example firewall
+        let blocked = set(&[(5, 8), (0, 2), (4, 7)]);
+        assert_eq!(pairs(&blocked.gaps(Interval::new(0, 9))), [(3, 3), (9, 9)]);
+    }
+}
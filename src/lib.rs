@@ -1,7 +1,88 @@
-use std::{fs, env};
+use std::{env, fmt, fs, ops::Deref};
 
+use grid::Grid;
+use parser::{ParseError, Parser};
+
+extern crate self as aoc_rs;
+
+pub mod counter;
+pub mod cycle;
+pub mod grid;
+pub mod hex;
+pub mod interval;
+pub mod ocr;
 pub mod parser;
-mod regexparser;
+pub mod point;
+pub mod regexparser;
+pub mod scan;
+pub mod search;
+pub mod sparsegrid;
+pub mod streamparser;
+pub mod value;
+
+pub use aoc_rs_macros::{
+    reparse, reparse_all, reparse_into, reparse_lines, scan, try_reparse, try_reparse_all, try_reparse_into, AocParse,
+};
+
+/// A day's puzzle input, from [`Input::fetch`]. Derefs to the text itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Input(String);
+
+impl Input {
+    /// Like [`get_input`], wrapped up as an `Input`.
+    pub fn fetch(year: usize, day: usize) -> anyhow::Result<Self> {
+        get_input(year, day).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    pub fn parser(&self) -> Parser<'_> {
+        Parser::new(&self.0)
+    }
+
+    /// The whole input as a grid, one `map`ped character per cell. See
+    /// [`Parser::grid`].
+    pub fn grid<T>(&self, map: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        Grid::parse(&self.0, map)
+    }
+
+    /// Like [`Input::grid`], but short rows are padded out with `fill`.
+    pub fn grid_padded<T: Clone>(&self, fill: T, map: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        Grid::parse_padded(&self.0, fill, map)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self(text)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self(text.to_owned())
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
 pub fn get_input(year: usize, day: usize) -> anyhow::Result<String> {
     let cache_file_name = format!(".cache/aoc/{}/{}", year, day);
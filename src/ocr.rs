@@ -0,0 +1,234 @@
+//! Reads the block letters some puzzles draw instead of giving an answer:
+//! the 4x6 font (2016 day 8, 2019 days 8 and 11, 2022 day 10) and the 6x10
+//! one (2018 day 10). Letters are told apart by the blank columns between
+//! them, so the spacing doesn't matter.
+
+use std::{collections::HashMap, error::Error, fmt};
+
+use once_cell::sync::Lazy;
+
+use crate::grid::Grid;
+
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Each font keyed by its glyphs drawn as `#`/`.` rows joined by newlines.
+type Font = HashMap<String, char>;
+
+static SMALL: Lazy<Font> = Lazy::new(|| SMALL_FONT.iter().map(|(c, rows)| (rows.join("\n"), *c)).collect());
+static LARGE: Lazy<Font> = Lazy::new(|| LARGE_FONT.iter().map(|(c, rows)| (rows.join("\n"), *c)).collect());
+
+/// A glyph that isn't in the font, drawn with `#` and `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGlyph {
+    pub column: usize,
+    pub pixels: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Only 6 and 10 pixel tall text can be read.
+    Height(usize),
+    /// `text` is what could be read, with a `?` for each unknown glyph.
+    UnknownGlyphs { text: String, glyphs: Vec<UnknownGlyph> },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Height(height) => write!(f, "can't read text {height} pixels tall, only 6 or 10"),
+            OcrError::UnknownGlyphs { text, glyphs } => {
+                write!(f, "read {text:?} but didn't recognise {} glyph(s)", glyphs.len())?;
+                for glyph in glyphs {
+                    write!(f, "\n\nat column {}:\n{}", glyph.column, glyph.pixels)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Reads the letters in a grid of lit pixels exactly one font high.
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, OcrError> {
+    let font: &Font = match pixels.height() {
+        6 => &SMALL,
+        10 => &LARGE,
+        height => return Err(OcrError::Height(height)),
+    };
+    let lit = |x: usize| pixels.column(x).any(|&p| p);
+
+    let mut text = String::new();
+    let mut glyphs = vec![];
+    let mut x = 0;
+
+    while x < pixels.width() {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < pixels.width() && lit(x) {
+            x += 1;
+        }
+
+        let drawn = pixels
+            .rows()
+            .map(|row| row[start..x].iter().map(|&p| if p { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match font.get(&drawn) {
+            Some(&c) => text.push(c),
+            None => {
+                text.push('?');
+                glyphs.push(UnknownGlyph { column: start, pixels: drawn });
+            }
+        }
+    }
+
+    if glyphs.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnknownGlyphs { text, glyphs })
+    }
+}
+
+/// Reads the letters drawn by a set of lit `(x, y)` pixels, wherever they
+/// are on the plane.
+pub fn recognize_points(points: impl IntoIterator<Item = (i64, i64)>) -> Result<String, OcrError> {
+    let points: Vec<_> = points.into_iter().collect();
+    let (Some(x0), Some(x1)) = (points.iter().map(|p| p.0).min(), points.iter().map(|p| p.0).max()) else {
+        return Ok(String::new());
+    };
+    let y0 = points.iter().map(|p| p.1).min().unwrap();
+    let y1 = points.iter().map(|p| p.1).max().unwrap();
+
+    let mut pixels = Grid::new((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize, false);
+    for (x, y) in points {
+        pixels[((x - x0) as usize, (y - y0) as usize)] = true;
+    }
+
+    recognize(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `text` in `font` the way the puzzles do, with `gap` blank
+    /// columns after each letter.
+    fn draw<const H: usize>(font: &[(char, [&str; H])], text: &str, gap: usize) -> Grid<bool> {
+        let rows: Vec<String> = (0..H)
+            .map(|y| {
+                text.chars()
+                    .map(|c| {
+                        let (_, glyph) = font.iter().find(|(g, _)| *g == c).unwrap();
+                        format!("{}{}", glyph[y], ".".repeat(gap))
+                    })
+                    .collect()
+            })
+            .collect();
+        Grid::parse(&rows.join("\n"), |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_small_font() {
+        assert_eq!(recognize(&draw(&SMALL_FONT, "ABCEFGHIJKLOPRSUYZ", 1)), Ok("ABCEFGHIJKLOPRSUYZ".into()));
+        assert_eq!(recognize(&draw(&SMALL_FONT, "HELLO", 3)), Ok("HELLO".into()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        assert_eq!(recognize(&draw(&LARGE_FONT, "ABCEFGHJKLNPRXZ", 2)), Ok("ABCEFGHJKLNPRXZ".into()));
+    }
+
+    #[test]
+    fn test_screens() {
+        // drawn by hand, not from the font tables
+        let small = "\
+###..#..#..##..####..##....##.###..###..
+#..#.#.#..#..#....#.#..#....#.#..#.#..#.
+#..#.##...#..#...#..#..#....#.###..#..#.
+###..#.#..####..#...####....#.#..#.###..
+#.#..#.#..#..#.#....#..#.#..#.#..#.#.#..
+#..#.#..#.#..#.####.#..#..##..###..#..#.";
+        let pixels = Grid::parse(small, |c| Some(c == '#')).unwrap();
+        assert_eq!(recognize(&pixels), Ok("RKAZAJBR".into()));
+
+        let large = "\
+#....#..#####...######
+#....#..#....#..#.....
+.#..#...#....#..#.....
+.#..#...#....#..#.....
+..##....#####...#####.
+..##....#..#....#.....
+.#..#...#...#...#.....
+.#..#...#...#...#.....
+#....#..#....#..#.....
+#....#..#....#..######";
+        let pixels = Grid::parse(large, |c| Some(c == '#')).unwrap();
+        assert_eq!(recognize(&pixels), Ok("XRE".into()));
+    }
+
+    #[test]
+    fn test_points() {
+        let pixels = draw(&SMALL_FONT, "HI", 1);
+        let points = pixels.positions().filter(|&p| pixels[p]).map(|(x, y)| (x as i64 - 100, y as i64 + 7));
+        assert_eq!(recognize_points(points), Ok("HI".into()));
+        assert_eq!(recognize_points([]), Ok(String::new()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let pixels = Grid::parse("#.#.##\n.#..##\n#.#.##", |c| Some(c == '#')).unwrap();
+        assert_eq!(recognize(&pixels), Err(OcrError::Height(3)));
+
+        let mut pixels = draw(&SMALL_FONT, "LOL", 1);
+        pixels[(5, 0)] = true;
+        let error = recognize(&pixels).unwrap_err();
+        assert_eq!(error, OcrError::UnknownGlyphs {
+            text: "L?L".into(),
+            glyphs: vec![UnknownGlyph { column: 5, pixels: "###.\n#..#\n#..#\n#..#\n#..#\n.##.".into() }],
+        });
+        assert!(error.to_string().starts_with("read \"L?L\" but didn't recognise 1 glyph(s)"));
+    }
+}
@@ -1,29 +1,254 @@
-use std::str::Chars;
-use itertools::Itertools;
+use std::{fmt, str::{Chars, FromStr}};
+
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    NoDigits,
+    Overflow,
+    InvalidRadix(u32),
+    InvalidFloat(String),
+    Expected { expected: String, found: Option<char> },
+    Invalid { expected: String, text: String },
+    RaggedRow { line: usize, width: usize, expected: usize },
+    Io(String),
+    InvalidUtf8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub at: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(at: usize, kind: ParseErrorKind) -> Self {
+        Self { at, kind }
+    }
+
+    /// The span of input the error points at: the offending character, or
+    /// an empty span at the end of input.
+    pub fn span(&self, input: &str) -> Span {
+        let len = input[self.at..].chars().next().map_or(0, char::len_utf8);
+        Span::new(self.at, self.at + len)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::NoDigits => write!(f, "expected a number at offset {}", self.at),
+            ParseErrorKind::Overflow => write!(f, "number at offset {} is out of range", self.at),
+            ParseErrorKind::InvalidRadix(base) => write!(f, "radix {} is not in 2..=36", base),
+            ParseErrorKind::InvalidFloat(text) => write!(f, "invalid float {:?} at offset {}", text, self.at),
+            ParseErrorKind::Expected { expected, found } => {
+                write!(f, "expected {} at offset {} but found {:?}", expected, self.at, found)
+            }
+            ParseErrorKind::Invalid { expected, text } => {
+                write!(f, "{:?} at offset {} is not a valid {}", text, self.at, expected)
+            }
+            ParseErrorKind::RaggedRow { line, width, expected } => {
+                write!(f, "grid row {} is {} wide, expected {}", line, width, expected)
+            }
+            ParseErrorKind::Io(error) => write!(f, "read failed at offset {}: {}", self.at, error),
+            ParseErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 at offset {}", self.at),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Integer types the numeric parsers can accumulate into.
+pub trait Integer: Copy {
+    const ZERO: Self;
+    fn from_digit(digit: u32) -> Self;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($T:ty),*) => {
+        $(impl Integer for $T {
+            const ZERO: Self = 0;
+
+            fn from_digit(digit: u32) -> Self {
+                digit as Self
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$T>::checked_mul(self, other)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$T>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$T>::checked_sub(self, other)
+            }
+        })*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Every integer in `text`, with a `-` directly before the digits making it
+/// negative. Everything else is skipped, so `"x=-3, y=4"` gives `[-3, 4]`;
+/// use [`uints`] when dashes are separators, as in `"2-4"`.
+///
+/// Panics on a number that doesn't fit `T`; see [`try_ints`].
+pub fn ints<T: Integer>(text: &str) -> impl Iterator<Item = T> + '_ {
+    try_ints(text).map(|number| number.unwrap_or_else(|e| panic!("{e}")))
+}
+
+/// Every run of digits in `text`, ignoring any signs.
+///
+/// Panics on a number that doesn't fit `T`; see [`try_uints`].
+pub fn uints<T: Integer>(text: &str) -> impl Iterator<Item = T> + '_ {
+    try_uints(text).map(|number| number.unwrap_or_else(|e| panic!("{e}")))
+}
+
+/// Fallible [`ints`]: a number that doesn't fit `T`, including a negative
+/// one for an unsigned `T`, is a [`ParseErrorKind::Overflow`] at its offset
+/// in `text`, and the numbers after it are still yielded.
+pub fn try_ints<T: Integer>(text: &str) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+    numbers(text, true)
+}
+
+/// Fallible [`uints`].
+pub fn try_uints<T: Integer>(text: &str) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+    numbers(text, false)
+}
+
+fn numbers<T: Integer>(text: &str, signed: bool) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+    let bytes = text.as_bytes();
+    let mut at = 0;
+
+    std::iter::from_fn(move || {
+        let digits = at + bytes[at..].iter().position(u8::is_ascii_digit)?;
+        let start = if signed && digits > at && bytes[digits - 1] == b'-' { digits - 1 } else { digits };
+        at = digits + bytes[digits..].iter().position(|b| !b.is_ascii_digit()).unwrap_or(bytes.len() - digits);
+
+        Some(Parser::new(&text[start..at]).try_int().map_err(|mut e: ParseError| {
+            e.at += start;
+            e
+        }))
+    })
+}
+
+/// A byte range of the input, `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn join(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    pub fn slice(self, input: &str) -> &str {
+        &input[self.start..self.end]
+    }
+
+    /// 1-based line and column (in chars) of the start of the span.
+    pub fn line_col(self, input: &str) -> (usize, usize) {
+        let before = &input[..self.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A parsed value along with the span of input it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned::new(f(self.value), self.span)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// One branch of [`Parser::alt`].
+pub type Alternative<'a, 'f, T> = dyn FnMut(&mut Parser<'a>) -> Result<T, ParseError> + 'f;
+
+/// A saved cursor position, see [`Parser::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+impl Checkpoint {
+    pub fn position(self) -> usize {
+        self.0
+    }
+}
 
 #[derive(Debug)]
-pub struct Parser {
-    state: String,
+pub struct Parser<'a> {
+    state: &'a str,
     at: usize,
 }
 
-impl Parser {
-    fn str_view(&self) -> &str {
+impl<'a> Parser<'a> {
+    fn str_view(&self) -> &'a str {
         &self.state[self.at..]
     }
 
-    fn chars(&self) -> Chars {
+    fn chars(&self) -> Chars<'a> {
         self.str_view().chars()
     }
 
-    pub fn new(data: &str) -> Self {
-        Self { state: data.to_owned(), at: 0 }
+    fn byte_view(&self) -> &'a [u8] {
+        &self.state.as_bytes()[self.at..]
+    }
+
+    pub fn new(data: &'a str) -> Self {
+        Self { state: data, at: 0 }
     }
 
     pub fn try_eat(&mut self, c: char) -> bool {
         let mut chars = self.chars();
         if chars.next() == Some(c) {
-            self.at += 1;
+            self.at += c.len_utf8();
             true
         } else {
             false
@@ -34,7 +259,7 @@ impl Parser {
         let mut chars = self.chars();
         let next = chars.next();
         if next == Some(c) {
-            self.at += 1;
+            self.at += c.len_utf8();
         } else {
             panic!("Tried to eat {} but found {:?}", c, next);
         }
@@ -46,53 +271,605 @@ impl Parser {
         }
     }
 
-    pub fn u64_base(&mut self, base: u32) -> u64 {
-        let mut chars = self.chars();
-
-        let number = chars
-            .take_while_ref(|&v| v.is_digit(base))
-            .map(|c| c.to_digit(base).expect("This was just checked to be a digit"))
-            .fold(0, |a, v| a * 10 + v as u64);
+    pub fn try_eat_str(&mut self, s: &str) -> bool {
+        if self.str_view().starts_with(s) {
+            self.at += s.len();
+            true
+        } else {
+            false
+        }
+    }
 
-        let new_state = chars.as_str();
-        let taken = self.state.len() - new_state.len();
-        self.at = taken;
+    /// Fallible `eat`, for use inside combinators.
+    pub fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.try_eat(c) {
+            Ok(())
+        } else {
+            Err(self.expected(format!("{c:?}")))
+        }
+    }
 
-        if taken > 0 {
-            number
+    /// Fallible `eat_str`, for use inside combinators.
+    pub fn expect_str(&mut self, s: &str) -> Result<(), ParseError> {
+        if self.try_eat_str(s) {
+            Ok(())
         } else {
-            panic!("Could not parse u64 from {}", self.str_view());
+            Err(self.expected(format!("{s:?}")))
         }
     }
 
+    pub fn expected(&self, expected: impl Into<String>) -> ParseError {
+        let kind = ParseErrorKind::Expected { expected: expected.into(), found: self.chars().next() };
+        ParseError::new(self.at, kind)
+    }
+
+    pub fn try_u64_base(&mut self, base: u32) -> Result<u64, ParseError> {
+        self.try_unsigned(base)
+    }
+
+    pub fn u64_base(&mut self, base: u32) -> u64 {
+        self.try_u64_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u64(&mut self) -> Result<u64, ParseError> {
+        self.try_u64_base(10)
+    }
+
     pub fn u64(&mut self) -> u64 {
         self.u64_base(10)
     }
 
-    pub fn i64_base(&mut self, base: u32) -> i64{
-        let negate = self.try_eat('-');
-        let unsigned: i64 = self.u64_base(base).try_into().expect("parsed signed outside the range of i64");
-        if negate {
-            -unsigned
-        } else {
-            unsigned
-        }
+    pub fn try_u64_auto(&mut self) -> Result<u64, ParseError> {
+        let start = self.at;
+        let base = self.radix_prefix().unwrap_or(10);
+        self.try_u64_base(base).map_err(|e| self.rewind(start, e))
+    }
+
+    pub fn u64_auto(&mut self) -> u64 {
+        self.try_u64_auto().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u128_base(&mut self, base: u32) -> Result<u128, ParseError> {
+        self.try_unsigned(base)
+    }
+
+    pub fn u128_base(&mut self, base: u32) -> u128 {
+        self.try_u128_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u128(&mut self) -> Result<u128, ParseError> {
+        self.try_u128_base(10)
+    }
+
+    pub fn u128(&mut self) -> u128 {
+        self.u128_base(10)
+    }
+
+    pub fn try_i64_base(&mut self, base: u32) -> Result<i64, ParseError> {
+        self.try_signed(base)
+    }
+
+    pub fn i64_base(&mut self, base: u32) -> i64 {
+        self.try_i64_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i64(&mut self) -> Result<i64, ParseError> {
+        self.try_i64_base(10)
     }
 
     pub fn i64(&mut self) -> i64 {
         self.i64_base(10)
     }
 
-    pub fn str_until(&mut self, delim: char) -> String {
+    pub fn try_i64_auto(&mut self) -> Result<i64, ParseError> {
+        let start = self.at;
+        let negate = self.sign();
+        let base = self.radix_prefix().unwrap_or(10);
+        let result = self.digits(base, negate);
+        result.map_err(|e| self.rewind(start, e))
+    }
+
+    pub fn i64_auto(&mut self) -> i64 {
+        self.try_i64_auto().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i128_base(&mut self, base: u32) -> Result<i128, ParseError> {
+        self.try_signed(base)
+    }
+
+    pub fn i128_base(&mut self, base: u32) -> i128 {
+        self.try_i128_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i128(&mut self) -> Result<i128, ParseError> {
+        self.try_i128_base(10)
+    }
+
+    pub fn i128(&mut self) -> i128 {
+        self.i128_base(10)
+    }
+
+    /// Parses an optionally signed decimal integer of any width.
+    pub fn try_int<T: Integer>(&mut self) -> Result<T, ParseError> {
+        self.try_signed(10)
+    }
+
+    pub fn int<T: Integer>(&mut self) -> T {
+        self.try_int().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_f64(&mut self) -> Result<f64, ParseError> {
+        let start = self.at;
+        let view = self.str_view().as_bytes();
+        let digits_from = |i: usize| view[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+        let mut len = 0;
+        if matches!(view.first(), Some(b'+' | b'-')) {
+            len += 1;
+        }
+        let mut mantissa = digits_from(len);
+        len += mantissa;
+        if view.get(len) == Some(&b'.') {
+            let fraction = digits_from(len + 1);
+            if mantissa + fraction > 0 {
+                mantissa += fraction;
+                len += 1 + fraction;
+            }
+        }
+        if mantissa == 0 {
+            return Err(ParseError::new(start, ParseErrorKind::NoDigits));
+        }
+        if matches!(view.get(len), Some(b'e' | b'E')) {
+            let sign = matches!(view.get(len + 1), Some(b'+' | b'-')) as usize;
+            let exponent = digits_from(len + 1 + sign);
+            if exponent > 0 {
+                len += 1 + sign + exponent;
+            }
+        }
+
+        let text = &self.str_view()[..len];
+        let value = text
+            .parse()
+            .map_err(|_| ParseError::new(start, ParseErrorKind::InvalidFloat(text.to_owned())))?;
+        self.at += len;
+        Ok(value)
+    }
+
+    pub fn f64(&mut self) -> f64 {
+        self.try_f64().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Eats a `0x`, `0o` or `0b` prefix if one is present and followed by a
+    /// digit of that radix, returning the radix it selects.
+    pub fn radix_prefix(&mut self) -> Option<u32> {
         let mut chars = self.chars();
-        let out = chars.take_while_ref(|&v| v != delim).collect();
-        self.at = self.state.len() - chars.as_str().len();
+        if chars.next() != Some('0') {
+            return None;
+        }
+        let base = match chars.next() {
+            Some('x' | 'X') => 16,
+            Some('o' | 'O') => 8,
+            Some('b' | 'B') => 2,
+            _ => return None,
+        };
+        if chars.next().is_some_and(|c| c.is_digit(base)) {
+            self.at += 2;
+            Some(base)
+        } else {
+            None
+        }
+    }
+
+    fn sign(&mut self) -> bool {
+        if self.try_eat('-') {
+            true
+        } else {
+            self.try_eat('+');
+            false
+        }
+    }
+
+    fn rewind(&mut self, to: usize, error: ParseError) -> ParseError {
+        self.at = to;
+        error
+    }
+
+    fn try_unsigned<T: Integer>(&mut self, base: u32) -> Result<T, ParseError> {
+        let start = self.at;
+        let result = self.digits(base, false);
+        result.map_err(|e| self.rewind(start, e))
+    }
+
+    fn try_signed<T: Integer>(&mut self, base: u32) -> Result<T, ParseError> {
+        let start = self.at;
+        let negate = self.sign();
+        let result = self.digits(base, negate);
+        result.map_err(|e| self.rewind(start, e))
+    }
+
+    /// Accumulates digits of `base` towards negative infinity when `negate`
+    /// is set, so the minimum value of a signed type is still reachable.
+    fn digits<T: Integer>(&mut self, base: u32, negate: bool) -> Result<T, ParseError> {
+        let start = self.at;
+        if !(2..=36).contains(&base) {
+            return Err(ParseError::new(start, ParseErrorKind::InvalidRadix(base)));
+        }
+
+        let mut number = T::ZERO;
+        let mut taken = 0;
+
+        for digit in self.byte_view().iter().map_while(|&b| (b as char).to_digit(base)) {
+            taken += 1;
+            number = number
+                .checked_mul(T::from_digit(base))
+                .and_then(|n| if negate {
+                    n.checked_sub(T::from_digit(digit))
+                } else {
+                    n.checked_add(T::from_digit(digit))
+                })
+                .ok_or_else(|| ParseError::new(start, ParseErrorKind::Overflow))?;
+        }
+
+        if taken == 0 {
+            return Err(ParseError::new(start, ParseErrorKind::NoDigits));
+        }
+
+        self.at += taken;
+        Ok(number)
+    }
+
+    pub fn str_until(&mut self, delim: char) -> &'a str {
+        let view = self.str_view();
+        let out = &view[..view.find(delim).unwrap_or(view.len())];
+        self.at += out.len();
         out
     }
 
+    /// Parses the text up to `delim`, or the rest of the input when `delim`
+    /// is `None`, with `FromStr`.
+    pub fn parse_until<T: FromStr>(&mut self, delim: Option<&str>) -> Result<T, ParseError> {
+        let view = self.str_view();
+        let len = match delim {
+            Some(delim) => view.find(delim).ok_or_else(|| self.expected(format!("{delim:?}")))?,
+            None => view.len(),
+        };
+        let text = &view[..len];
+        let value = text.parse().map_err(|_| {
+            let kind = ParseErrorKind::Invalid {
+                expected: std::any::type_name::<T>().to_owned(),
+                text: text.to_owned(),
+            };
+            ParseError::new(self.at, kind)
+        })?;
+        self.at += len;
+        Ok(value)
+    }
+
+    pub fn rest(&mut self) -> &'a str {
+        let out = self.str_view();
+        self.at = self.state.len();
+        out
+    }
+
+    pub fn peek_byte(&self) -> Option<u8> {
+        self.byte_view().first().copied()
+    }
+
+    /// Byte-oriented `try_eat`. Only ASCII bytes are accepted, so the cursor
+    /// always stays on a char boundary.
+    pub fn try_eat_byte(&mut self, b: u8) -> bool {
+        if b.is_ascii() && self.peek_byte() == Some(b) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes bytes while `pred` holds. Only ASCII bytes are ever matched, so
+    /// the cursor always stays on a char boundary.
+    pub fn bytes_while(&mut self, mut pred: impl FnMut(u8) -> bool) -> &'a [u8] {
+        let view = self.byte_view();
+        let len = view.iter().take_while(|&&b| b.is_ascii() && pred(b)).count();
+        self.at += len;
+        &view[..len]
+    }
+
+    pub fn bytes_until(&mut self, delim: u8) -> &'a [u8] {
+        assert!(delim.is_ascii(), "bytes_until needs an ASCII delimiter");
+        let view = self.byte_view();
+        let len = view.iter().position(|&b| b == delim).unwrap_or(view.len());
+        self.at += len;
+        &view[..len]
+    }
+
     pub fn done(&self) -> bool {
         self.state.len() == self.at
     }
+
+    pub fn expect_done(&self) -> Result<(), ParseError> {
+        if self.done() {
+            Ok(())
+        } else {
+            Err(self.expected("end of input"))
+        }
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.chars().next()
+    }
+
+    pub fn peek_str(&self, s: &str) -> bool {
+        self.str_view().starts_with(s)
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        let view = self.str_view();
+        self.at += view.len() - view.trim_start().len();
+    }
+
+    /// Takes characters up to the next whitespace or the end of input.
+    pub fn word(&mut self) -> &'a str {
+        let view = self.str_view();
+        let out = &view[..view.find(char::is_whitespace).unwrap_or(view.len())];
+        self.at += out.len();
+        out
+    }
+
+    /// Takes an ASCII identifier: a letter or `_`, then letters, digits or `_`.
+    pub fn identifier(&mut self) -> Result<&'a str, ParseError> {
+        let view = self.str_view();
+        if !self.peek().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') {
+            return Err(self.expected("an identifier"));
+        }
+        let len = view.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(view.len());
+        self.at += len;
+        Ok(&view[..len])
+    }
+
+    /// Takes exactly `n` characters.
+    pub fn take(&mut self, n: usize) -> Result<&'a str, ParseError> {
+        let view = self.str_view();
+        let len = match view.char_indices().nth(n) {
+            Some((i, _)) => i,
+            None if view.chars().count() == n => view.len(),
+            None => return Err(self.expected(format!("{n} more characters"))),
+        };
+        self.at += len;
+        Ok(&view[..len])
+    }
+
+    /// Eats a line ending, which may be `\n`, `\r\n` or the end of input.
+    pub fn eol(&mut self) -> Result<(), ParseError> {
+        if self.done() || self.try_eat('\n') || self.try_eat_str("\r\n") {
+            Ok(())
+        } else {
+            Err(self.expected("end of line"))
+        }
+    }
+
+    /// Splits off the rest of the current line as its own parser and moves
+    /// past the line ending. Offsets in the sub-parser's errors still refer
+    /// to the whole input.
+    pub fn line(&mut self) -> Parser<'a> {
+        let view = self.str_view();
+        let len = view.find('\n').unwrap_or(view.len());
+        let line = &view[..len];
+        let end = self.at + line.strip_suffix('\r').unwrap_or(line).len();
+        let line = Parser { state: &self.state[..end], at: self.at };
+        self.at += len;
+        self.try_eat('\n');
+        line
+    }
+
+    /// Parses lines of `map`ped characters up to a blank line or the end of
+    /// input. Every row must be the same width.
+    pub fn grid<T>(&mut self, map: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        self.grid_rows(None, map)
+    }
+
+    /// Like [`Parser::grid`], but short rows are padded out with `fill` to the
+    /// width of the longest row.
+    pub fn grid_padded<T: Clone>(&mut self, fill: T, map: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        self.grid_rows(Some(&mut || fill.clone()), map)
+    }
+
+    fn grid_rows<T>(
+        &mut self,
+        mut fill: Option<&mut dyn FnMut() -> T>,
+        mut map: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let first_line = self.state[..self.at].matches('\n').count() + 1;
+
+        let rows = self.attempt(|p| {
+            let mut rows: Vec<Vec<T>> = Vec::new();
+
+            while !p.done() && !p.peek_str("\n") && !p.peek_str("\r\n") {
+                let row_start = p.at;
+                let mut line = p.line();
+                let mut row = Vec::new();
+                while let Some(c) = line.peek() {
+                    row.push(map(c).ok_or_else(|| line.expected("a grid cell"))?);
+                    line.at += c.len_utf8();
+                }
+
+                if fill.is_none() && rows.first().is_some_and(|first| first.len() != row.len()) {
+                    let kind = ParseErrorKind::RaggedRow {
+                        line: first_line + rows.len(),
+                        width: row.len(),
+                        expected: rows[0].len(),
+                    };
+                    return Err(ParseError::new(row_start, kind));
+                }
+                rows.push(row);
+            }
+
+            if rows.is_empty() {
+                return Err(p.expected("a grid"));
+            }
+            Ok(rows)
+        })?;
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            if let Some(fill) = &mut fill {
+                row.resize_with(width, fill);
+            }
+            cells.extend(row);
+        }
+
+        Ok(Grid::from_vec(width, height, cells))
+    }
+
+    /// Current byte offset into the input.
+    pub fn position(&self) -> usize {
+        self.at
+    }
+
+    pub fn span_since(&self, checkpoint: Checkpoint) -> Span {
+        Span::new(checkpoint.0, self.at)
+    }
+
+    /// Runs `f` and records the span of input it consumed.
+    pub fn spanned<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<Spanned<T>, ParseError> {
+        let checkpoint = self.checkpoint();
+        let value = f(self)?;
+        Ok(Spanned::new(value, self.span_since(checkpoint)))
+    }
+
+    pub fn try_int_spanned<T: Integer>(&mut self) -> Result<Spanned<T>, ParseError> {
+        self.spanned(Parser::try_int)
+    }
+
+    pub fn int_spanned<T: Integer>(&mut self) -> Spanned<T> {
+        self.try_int_spanned().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn word_spanned(&mut self) -> Spanned<&'a str> {
+        let checkpoint = self.checkpoint();
+        let word = self.word();
+        Spanned::new(word, self.span_since(checkpoint))
+    }
+
+    pub fn str_until_spanned(&mut self, delim: char) -> Spanned<&'a str> {
+        let checkpoint = self.checkpoint();
+        let text = self.str_until(delim);
+        Spanned::new(text, self.span_since(checkpoint))
+    }
+
+    pub fn identifier_spanned(&mut self) -> Result<Spanned<&'a str>, ParseError> {
+        self.spanned(Parser::identifier)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.at)
+    }
+
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.at = checkpoint.0;
+    }
+
+    /// Runs `f`, rewinding to where it started if it fails.
+    pub fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let checkpoint = self.checkpoint();
+        f(self).inspect_err(|_| self.restore(checkpoint))
+    }
+
+    pub fn optional<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Option<T> {
+        self.attempt(f).ok()
+    }
+
+    /// Applies `f` until it fails or stops consuming input.
+    pub fn many<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Vec<T> {
+        let mut out = Vec::new();
+        loop {
+            let before = self.at;
+            match self.attempt(&mut f) {
+                Ok(v) => out.push(v),
+                Err(_) => break,
+            }
+            if self.at == before {
+                break;
+            }
+        }
+        out
+    }
+
+    pub fn many1<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let first = self.attempt(&mut f)?;
+        let mut out = vec![first];
+        out.extend(self.many(f));
+        Ok(out)
+    }
+
+    /// Parses zero or more `f` separated by `sep`. A trailing separator is
+    /// left unconsumed.
+    pub fn sep_by<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>, sep: &str) -> Vec<T> {
+        match self.attempt(&mut f) {
+            Ok(first) => {
+                let mut out = vec![first];
+                out.extend(self.many(|p| {
+                    p.expect_str(sep)?;
+                    f(p)
+                }));
+                out
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn sep_by1<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>, sep: &str) -> Result<Vec<T>, ParseError> {
+        let checkpoint = self.checkpoint();
+        let out = self.sep_by(&mut f, sep);
+        if out.is_empty() {
+            self.restore(checkpoint);
+            Err(self.attempt(f).err().unwrap_or_else(|| self.expected("an item")))
+        } else {
+            Ok(out)
+        }
+    }
+
+    pub fn delimited<T>(
+        &mut self,
+        open: &str,
+        f: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+        close: &str,
+    ) -> Result<T, ParseError> {
+        self.attempt(|p| {
+            p.expect_str(open)?;
+            let out = f(p)?;
+            p.expect_str(close)?;
+            Ok(out)
+        })
+    }
+
+    /// Parses `open`, items separated by `sep`, then `close`, like `[1,2,3]`.
+    pub fn list_of<T>(
+        &mut self,
+        open: &str,
+        sep: &str,
+        close: &str,
+        f: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.delimited(open, |p| Ok(p.sep_by(f, sep)), close)
+    }
+
+    /// Tries each alternative in turn from the same position. If all of them
+    /// fail, returns the error that got furthest into the input.
+    pub fn alt<T>(&mut self, alternatives: &mut [&mut Alternative<'a, '_, T>]) -> Result<T, ParseError> {
+        let mut furthest: Option<ParseError> = None;
+        for f in alternatives {
+            match self.attempt(f) {
+                Ok(v) => return Ok(v),
+                Err(e) => if furthest.as_ref().is_none_or(|f| e.at >= f.at) {
+                    furthest = Some(e);
+                },
+            }
+        }
+        Err(furthest.unwrap_or_else(|| self.expected("one of no alternatives")))
+    }
 }
 
 #[cfg(test)]
@@ -201,4 +978,440 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_radix() {
+        assert_eq!(Parser::new("ff").u64_base(16), 255);
+        assert_eq!(Parser::new("1011").u64_base(2), 11);
+        assert_eq!(Parser::new("777").u64_base(8), 511);
+        assert_eq!(Parser::new("zz").u64_base(36), 1295);
+        assert_eq!(Parser::new("-7f").i64_base(16), -127);
+
+        let mut parser = Parser::new("102");
+        assert_eq!(parser.u64_base(2), 2);
+        parser.eat('2');
+        assert!(parser.done());
+
+        let error = Parser::new("10").try_u64_base(37).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidRadix(37));
+    }
+
+    #[test]
+    fn test_radix_prefix() {
+        let mut parser = Parser::new("0xff 0b101 0o17 42 -0x10 +0b11");
+        let mut found = vec![];
+        while !parser.done() {
+            found.push(parser.i64_auto());
+            parser.try_eat(' ');
+        }
+
+        assert_eq!(found, vec![255, 5, 15, 42, -16, 3]);
+
+        let mut parser = Parser::new("0xg");
+        assert_eq!(parser.u64_auto(), 0);
+        parser.eat_str("xg");
+    }
+
+    #[test]
+    fn test_plus_sign() {
+        let mut parser = Parser::new("+1 -2 +3");
+        let mut found = vec![];
+        while !parser.done() {
+            found.push(parser.i64());
+            parser.try_eat(' ');
+        }
+
+        assert_eq!(found, vec![1, -2, 3]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Parser::new("18446744073709551615").u64(), u64::MAX);
+        assert_eq!(Parser::new("-9223372036854775808").i64(), i64::MIN);
+        assert_eq!(Parser::new("9223372036854775807").i64(), i64::MAX);
+
+        let mut parser = Parser::new("18446744073709551616");
+        let error = parser.try_u64().unwrap_err();
+        assert_eq!(error, ParseError::new(0, ParseErrorKind::Overflow));
+        assert_eq!(parser.u128(), 18446744073709551616);
+
+        let mut parser = Parser::new("x-9223372036854775809");
+        parser.eat('x');
+        assert_eq!(parser.try_i64().unwrap_err(), ParseError::new(2, ParseErrorKind::Overflow));
+        assert_eq!(parser.i128(), -9223372036854775809);
+    }
+
+    #[test]
+    fn test_no_digits() {
+        let mut parser = Parser::new("-x");
+        assert_eq!(parser.try_i64().unwrap_err().kind, ParseErrorKind::NoDigits);
+        parser.eat('-');
+        assert_eq!(parser.try_u64().unwrap_err(), ParseError::new(1, ParseErrorKind::NoDigits));
+    }
+
+    #[test]
+    fn test_floats() {
+        let mut parser = Parser::new("1.5,-2,+.25,3e2,1.5e-1,7.x");
+        let mut found = vec![];
+        while !parser.done() {
+            found.push(parser.f64());
+            if !parser.try_eat(',') {
+                parser.eat('x');
+            }
+        }
+
+        assert_eq!(found, vec![1.5, -2.0, 0.25, 300.0, 0.15, 7.0]);
+        assert!(Parser::new(".e5").try_f64().is_err());
+    }
+
+    #[test]
+    fn test_borrows_input() {
+        let string = String::from("Hello World");
+        let mut parser = Parser::new(&string);
+        let hello = parser.str_until(' ');
+        parser.eat(' ');
+        let world = parser.rest();
+
+        assert_eq!(hello.as_ptr(), string.as_ptr());
+        assert_eq!(world.as_ptr(), string[6..].as_ptr());
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut parser = Parser::new("dabAcCaCBAcCcaDA\nxyz");
+        assert_eq!(parser.peek_byte(), Some(b'd'));
+        assert_eq!(parser.bytes_while(|b| b.is_ascii_lowercase()), b"dab");
+        assert_eq!(parser.bytes_until(b'\n'), b"AcCaCBAcCcaDA");
+        assert!(!parser.try_eat_byte(b'x'));
+        assert!(parser.try_eat_byte(b'\n'));
+        assert_eq!(parser.bytes_until(b'\n'), b"xyz");
+        assert_eq!(parser.peek_byte(), None);
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut parser = Parser::new("abc");
+        let checkpoint = parser.checkpoint();
+        parser.eat_str("ab");
+        parser.restore(checkpoint);
+        parser.eat_str("abc");
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_combinator_list() {
+        let mut parser = Parser::new("[1 -2 4 -5 10 -100 1000]");
+        let found = parser.list_of("[", " ", "]", Parser::try_i64).unwrap();
+        assert_eq!(found, vec![1, -2, 4, -5, 10, -100, 1000]);
+        assert!(parser.done());
+
+        let mut parser = Parser::new("[]");
+        assert_eq!(parser.list_of("[", ",", "]", Parser::try_i64).unwrap(), vec![]);
+
+        let mut parser = Parser::new("[1,2,]");
+        assert!(parser.list_of("[", ",", "]", Parser::try_i64).is_err());
+        assert_eq!(parser.checkpoint(), Checkpoint(0));
+    }
+
+    #[test]
+    fn test_sep_by() {
+        let mut parser = Parser::new("498,4 -> 498,6 -> 496,6");
+        let path = parser.sep_by(|p| {
+            let x = p.try_int::<i32>()?;
+            p.expect(',')?;
+            Ok((x, p.try_int::<i32>()?))
+        }, " -> ");
+
+        assert_eq!(path, vec![(498, 4), (498, 6), (496, 6)]);
+        assert!(parser.done());
+
+        let mut parser = Parser::new("1, 2, x");
+        assert_eq!(parser.sep_by(Parser::try_u64, ", "), vec![1, 2]);
+        parser.eat_str(", x");
+
+        assert!(Parser::new("x").sep_by1(Parser::try_u64, ",").is_err());
+    }
+
+    #[test]
+    fn test_many_and_optional() {
+        let mut parser = Parser::new("aaab");
+        assert_eq!(parser.many(|p| p.expect('a')).len(), 3);
+        assert_eq!(parser.optional(|p| p.expect('a')), None);
+        assert_eq!(parser.optional(|p| p.expect('b')), Some(()));
+        assert!(parser.many1(|p| p.expect('a')).is_err());
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_alt() {
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Begin(i64),
+            Sleep,
+            Wake,
+        }
+
+        let events = ["Guard #10 begins shift", "falls asleep", "wakes up"]
+            .map(|line| Parser::new(line).alt(&mut [
+                &mut |p| {
+                    p.expect_str("Guard #")?;
+                    let guard = p.try_i64()?;
+                    p.expect_str(" begins shift")?;
+                    Ok(Event::Begin(guard))
+                },
+                &mut |p| p.expect_str("falls asleep").map(|_| Event::Sleep),
+                &mut |p| p.expect_str("wakes up").map(|_| Event::Wake),
+            ]));
+
+        assert_eq!(events, [Ok(Event::Begin(10)), Ok(Event::Sleep), Ok(Event::Wake)]);
+
+        let mut parser = Parser::new("Guard #10 ends shift");
+        let error = parser.alt(&mut [
+            &mut |p| p.delimited("Guard #", Parser::try_i64, " begins shift"),
+            &mut |p| p.expect_str("wakes up").map(|_| 0),
+        ]).unwrap_err();
+
+        assert_eq!(error.at, 9);
+        assert_eq!(parser.checkpoint(), Checkpoint(0));
+    }
+
+    #[test]
+    fn test_lookahead() {
+        let mut parser = Parser::new("[1518-11-01 00:00] Guard #10 begins shift");
+        assert_eq!(parser.peek(), Some('['));
+        assert!(parser.peek_str("[1518"));
+        assert!(!parser.peek_str("[1519"));
+        assert_eq!(parser.str_until(']'), "[1518-11-01 00:00");
+        parser.eat(']');
+        parser.skip_whitespace();
+        assert_eq!(parser.word(), "Guard");
+        parser.skip_whitespace();
+        assert_eq!(parser.take(1), Ok("#"));
+        assert_eq!(parser.u64(), 10);
+        parser.skip_whitespace();
+        assert_eq!(parser.rest(), "begins shift");
+        assert_eq!(parser.peek(), None);
+        assert!(parser.expect_done().is_ok());
+    }
+
+    #[test]
+    fn test_identifier_and_take() {
+        let mut parser = Parser::new("_foo1 bar 9x");
+        assert_eq!(parser.identifier(), Ok("_foo1"));
+        parser.skip_whitespace();
+        assert_eq!(parser.take(3), Ok("bar"));
+        parser.skip_whitespace();
+        assert!(parser.identifier().is_err());
+        assert!(parser.take(3).is_err());
+        assert_eq!(parser.take(2), Ok("9x"));
+        assert_eq!(parser.take(0), Ok(""));
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut parser = Parser::new("1 2\r\n3\n\n4");
+        let mut lines = vec![];
+        while !parser.done() {
+            let mut line = parser.line();
+            lines.push(line.many(|p| {
+                let v = p.try_u64()?;
+                p.skip_whitespace();
+                Ok(v)
+            }));
+            assert!(line.expect_done().is_ok());
+        }
+
+        assert_eq!(lines, vec![vec![1, 2], vec![3], vec![], vec![4]]);
+    }
+
+    #[test]
+    fn test_eol_and_done() {
+        let mut parser = Parser::new("a\nb");
+        parser.eat('a');
+        assert_eq!(parser.expect_done().unwrap_err().at, 1);
+        assert!(parser.eol().is_ok());
+        assert!(parser.eol().is_err());
+        parser.eat('b');
+        assert!(parser.eol().is_ok());
+        assert!(parser.done());
+
+        let mut parser = Parser::new("x\ny");
+        let mut line = parser.line();
+        assert_eq!(line.rest(), "x");
+        assert!(line.done());
+        assert_eq!(parser.rest(), "y");
+    }
+
+    #[test]
+    fn test_utf8() {
+        let mut parser = Parser::new("é→1,ü");
+        parser.eat('é');
+        assert!(parser.try_eat('→'));
+        assert_eq!(parser.u64(), 1);
+        parser.eat(',');
+        assert_eq!(parser.peek(), Some('ü'));
+        assert_eq!(parser.take(1), Ok("ü"));
+        assert!(parser.done());
+
+        let mut parser = Parser::new("ü x");
+        parser.eat('ü');
+        assert_eq!(parser.str_until('x'), " ");
+    }
+
+    #[test]
+    fn test_spans() {
+        let input = "move 13 from é2 to 3";
+        let mut parser = Parser::new(input);
+        parser.eat_str("move ");
+        let count = parser.spanned(Parser::try_u64).unwrap();
+        assert_eq!(count, Spanned::new(13, Span::new(5, 7)));
+        assert_eq!(count.span.slice(input), "13");
+        assert_eq!(*count + 1, 14);
+
+        parser.eat_str(" from é");
+        let from = parser.spanned(Parser::try_u64).unwrap();
+        assert_eq!(from.span, Span::new(15, 16));
+        assert_eq!(from.span.line_col(input), (1, 15));
+
+        parser.eat_str(" to ");
+        let error = parser.expect_done().unwrap_err();
+        assert_eq!(error.span(input), Span::new(20, 21));
+    }
+
+    #[test]
+    fn test_spanned_primitives() {
+        let input = "turn on 499,-12 through x";
+        let mut parser = Parser::new(input);
+
+        let word = parser.word_spanned();
+        assert_eq!((word.value, word.span), ("turn", Span::new(0, 4)));
+        parser.eat(' ');
+        assert_eq!(parser.identifier_spanned().unwrap().span.slice(input), "on");
+        parser.eat(' ');
+
+        let x = parser.str_until_spanned(',');
+        assert_eq!((x.value, x.span), ("499", Span::new(8, 11)));
+        parser.eat(',');
+        assert_eq!(parser.int_spanned::<i32>(), Spanned::new(-12, Span::new(12, 15)));
+
+        parser.eat_str(" through ");
+        let error = parser.try_int_spanned::<i32>().unwrap_err();
+        assert_eq!(error.at, 24);
+        assert!(parser.identifier_spanned().is_ok());
+    }
+
+    #[test]
+    fn test_span_line_col() {
+        let input = "ab\ncd\néf";
+        let mut parser = Parser::new(input);
+        parser.line();
+        parser.line();
+        parser.eat('é');
+        let error = parser.expect('x').unwrap_err();
+        assert_eq!(error.span(input).line_col(input), (3, 2));
+        assert_eq!(error.span(input).slice(input), "f");
+    }
+
+    #[test]
+    fn test_parse_until() {
+        let mut parser = Parser::new("12x-3:abc");
+        assert_eq!(parser.parse_until::<u8>(Some("x")), Ok(12));
+        parser.eat('x');
+        assert_eq!(parser.parse_until::<i32>(Some(":")), Ok(-3));
+        parser.eat(':');
+
+        let error = parser.parse_until::<u8>(None).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::Invalid { expected: "u8".into(), text: "abc".into() });
+        assert!(parser.parse_until::<String>(Some(";")).is_err());
+        assert_eq!(parser.parse_until::<String>(None), Ok("abc".into()));
+        assert!(parser.done());
+    }
+
+    #[derive(Debug, PartialEq, crate::AocParse)]
+    #[aoc(format = "#{number} @ {point.0},{point.1}: {w}x{h}")]
+    struct Claim {
+        number: u64,
+        point: (u64, u64),
+        w: u64,
+        h: u64,
+    }
+
+    #[derive(Debug, PartialEq, crate::AocParse)]
+    #[aoc(format = "{lo}-{hi} {name}-{weight}")]
+    struct Range {
+        lo: i32,
+        hi: i32,
+        name: String,
+        weight: f64,
+    }
+
+    #[derive(Debug, PartialEq, crate::AocParse)]
+    enum Instruction {
+        #[aoc(format = "addx {0}")]
+        Addx(i64),
+        #[aoc(format = "move {count} from {from} to {to}")]
+        Move { count: usize, from: usize, to: usize },
+        #[aoc(format = "{{noop}}")]
+        Noop,
+    }
+
+    #[test]
+    fn test_derive_struct() {
+        let claim: Claim = "#123 @ 3,2: 5x4".parse().unwrap();
+        assert_eq!(claim, Claim { number: 123, point: (3, 2), w: 5, h: 4 });
+
+        assert!("#123 @ 3,2: 5x4 ".parse::<Claim>().is_err());
+        let error = "#123 @ 3,x: 5x4".parse::<Claim>().unwrap_err();
+        assert_eq!(error.at, 9);
+    }
+
+    #[test]
+    fn test_derive_numbers_containing_delimiters() {
+        assert_eq!("-3--1 a-0.5".parse(), Ok(Range { lo: -3, hi: -1, name: "a".into(), weight: 0.5 }));
+        assert_eq!("2-4 b--1.5".parse(), Ok(Range { lo: 2, hi: 4, name: "b".into(), weight: -1.5 }));
+
+        // other fields still end at the first occurrence of the next literal
+        assert!("2-4 a-b-1".parse::<Range>().is_err());
+        assert_eq!("2-4-6 a-1".parse::<Range>().unwrap_err().at, 3);
+    }
+
+    #[test]
+    fn test_derive_enum() {
+        assert_eq!("addx -11".parse(), Ok(Instruction::Addx(-11)));
+        assert_eq!("{noop}".parse(), Ok(Instruction::Noop));
+        assert_eq!("move 1 from 2 to 3".parse(), Ok(Instruction::Move { count: 1, from: 2, to: 3 }));
+        assert!("noop".parse::<Instruction>().is_err());
+        assert!("addx".parse::<Instruction>().is_err());
+    }
+
+    #[test]
+    fn test_ints() {
+        let sensor = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(ints::<i32>(sensor).collect::<Vec<_>>(), [2, -18, -2, 15]);
+        assert_eq!(uints::<u32>(sensor).collect::<Vec<_>>(), [2, 18, 2, 15]);
+
+        assert_eq!(ints::<i64>("2-4,6-8").collect::<Vec<_>>(), [2, -4, 6, -8]);
+        assert_eq!(uints::<u8>("2-4,6-8").collect::<Vec<_>>(), [2, 4, 6, 8]);
+        assert_eq!(ints::<i32>("- -1 --2 x").collect::<Vec<_>>(), [-1, -2]);
+        assert_eq!(ints::<i32>("no numbers").count(), 0);
+    }
+
+    #[test]
+    fn test_try_ints() {
+        let numbers: Vec<_> = try_uints::<u8>("1 300 2").collect();
+        assert_eq!(numbers, [Ok(1), Err(ParseError::new(2, ParseErrorKind::Overflow)), Ok(2)]);
+
+        let numbers: Vec<_> = try_ints::<u32>("x=-3, y=4").collect();
+        assert_eq!(numbers, [Err(ParseError::new(3, ParseErrorKind::Overflow)), Ok(4)]);
+        assert_eq!(try_ints::<i8>("-128 128").collect::<Vec<_>>(), [Ok(-128), Err(ParseError::new(5, ParseErrorKind::Overflow))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "number at offset 2 is out of range")]
+    fn test_ints_overflow() {
+        uints::<u8>("1 300").for_each(drop);
+    }
+}
@@ -0,0 +1,462 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::parser::{ParseError, ParseErrorKind, Parser};
+
+/// A position or offset on the plane. Like [`Grid`](crate::grid::Grid),
+/// `y` grows downwards, so [`Direction::Up`] is `(0, -1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Add<Output = T> + From<i8>> Point2<T> {
+    /// The neighbouring point in `direction`. Needs signed coordinates; use
+    /// [`checked_step`](Point2::checked_step) on unsigned ones.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+}
+
+macro_rules! impl_checked_step {
+    ($($t:ty => $signed:ty),*) => {
+        $(
+            impl Point2<$t> {
+                /// The neighbouring point in `direction`, or `None` when it
+                /// would leave the range of the coordinate type.
+                pub fn checked_step(self, direction: Direction) -> Option<Self> {
+                    let delta = direction.delta::<$signed>();
+                    Some(Self::new(self.x.checked_add_signed(delta.x)?, self.y.checked_add_signed(delta.y)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_step!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize);
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Self::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty),*) => {
+        $(
+            impl Point2<$t> {
+                /// Panics when the distance doesn't fit in the coordinate type.
+                pub fn manhattan(self, other: Self) -> $t {
+                    self.x
+                        .abs_diff(other.x)
+                        .checked_add(self.y.abs_diff(other.y))
+                        .and_then(|distance| <$t>::try_from(distance).ok())
+                        .expect("manhattan distance overflows the coordinate type")
+                }
+
+                /// Panics when the distance doesn't fit in the coordinate type.
+                pub fn chebyshev(self, other: Self) -> $t {
+                    <$t>::try_from(self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)))
+                        .expect("chebyshev distance overflows the coordinate type")
+                }
+            }
+        )*
+    };
+}
+
+impl_distances!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_signum {
+    ($($t:ty),*) => {
+        $(
+            impl Point2<$t> {
+                /// Each coordinate clamped to -1, 0 or 1: the single king's
+                /// move step towards this offset.
+                pub fn signum(self) -> Self {
+                    Self::new(self.x.signum(), self.y.signum())
+                }
+            }
+        )*
+    };
+}
+
+impl_signum!(i8, i16, i32, i64, i128, isize);
+
+/// Parses `x,y`, allowing spaces after the comma.
+impl<T: FromStr> FromStr for Point2<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let x = parser.parse_until(Some(","))?;
+        parser.expect(',')?;
+        parser.skip_whitespace();
+        let y = parser.parse_until(None)?;
+        Ok(Self::new(x, y))
+    }
+}
+
+/// A position or offset in space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// Neighbours take offsets of -1, so these need signed coordinates.
+impl<T: Add<Output = T> + From<i8> + Copy> Point3<T> {
+    /// The six points sharing a face with this one.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)]
+            .into_iter()
+            .map(move |(dx, dy, dz)| self + Point3::new(T::from(dx), T::from(dy), T::from(dz)))
+    }
+
+    /// The 26 points sharing a face, edge or corner with this one.
+    pub fn neighbors26(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+            .filter(|&d| d != (0, 0, 0))
+            .map(move |(dx, dy, dz)| self + Point3::new(T::from(dx), T::from(dy), T::from(dz)))
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(p: Point3<T>) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Self::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point3<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+        self.z -= other.z;
+    }
+}
+
+macro_rules! impl_distances3 {
+    ($($t:ty),*) => {
+        $(
+            impl Point3<$t> {
+                /// Panics when the distance doesn't fit in the coordinate type.
+                pub fn manhattan(self, other: Self) -> $t {
+                    self.x
+                        .abs_diff(other.x)
+                        .checked_add(self.y.abs_diff(other.y))
+                        .and_then(|distance| distance.checked_add(self.z.abs_diff(other.z)))
+                        .and_then(|distance| <$t>::try_from(distance).ok())
+                        .expect("manhattan distance overflows the coordinate type")
+                }
+
+                /// Panics when the distance doesn't fit in the coordinate type.
+                pub fn chebyshev(self, other: Self) -> $t {
+                    <$t>::try_from(self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)).max(self.z.abs_diff(other.z)))
+                        .expect("chebyshev distance overflows the coordinate type")
+                }
+            }
+        )*
+    };
+}
+
+impl_distances3!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Parses `x,y,z`, allowing spaces after the commas.
+impl<T: FromStr> FromStr for Point3<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let x = parser.parse_until(Some(","))?;
+        parser.expect(',')?;
+        parser.skip_whitespace();
+        let y = parser.parse_until(Some(","))?;
+        parser.expect(',')?;
+        parser.skip_whitespace();
+        let z = parser.parse_until(None)?;
+        Ok(Self::new(x, y, z))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise from `Up`, in the same order as
+    /// [`ORTHOGONAL`](crate::grid::ORTHOGONAL).
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The one step offset in this direction.
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::Right => (1, 0),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+/// The one step offset, as taken by [`Grid::ray`](crate::grid::Grid::ray).
+impl From<Direction> for (isize, isize) {
+    fn from(direction: Direction) -> Self {
+        direction.delta().into()
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseError;
+
+    /// Accepts `U/D/L/R`, `^v<>` and compass points `N/S/E/W`.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | '^' | 'N' => Ok(Self::Up),
+            'R' | '>' | 'E' => Ok(Self::Right),
+            'D' | 'v' | 'S' => Ok(Self::Down),
+            'L' | '<' | 'W' => Ok(Self::Left),
+            _ => Err(ParseError::new(0, ParseErrorKind::Invalid {
+                expected: "direction".to_owned(),
+                text: c.to_string(),
+            })),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(ParseError::new(0, ParseErrorKind::Invalid {
+                expected: "direction".to_owned(),
+                text: s.to_owned(),
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(1i64, -2);
+        let b = Point2::new(4, 2);
+        assert_eq!(a + b, Point2::new(5, 0));
+        assert_eq!(b - a, Point2::new(3, 4));
+        assert_eq!(a * 3, Point2::new(3, -6));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!((b - a).signum(), Point2::new(1, 1));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+
+        let mut c = a;
+        c += b;
+        c -= Point2::new(5, 0);
+        assert_eq!(c, Point2::new(0, 0));
+    }
+
+    #[test]
+    fn test_unsigned_distances() {
+        let a = Point2::new(1u64, 8);
+        let b = Point2::new(4, 2);
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(b.chebyshev(a), 6);
+
+        assert_eq!(Point2::new(0u8, 0).checked_step(Direction::Down), Some(Point2::new(0, 1)));
+        assert_eq!(Point2::new(0u8, 0).checked_step(Direction::Left), None);
+        assert_eq!(Point2::new(255u8, 0).checked_step(Direction::Right), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "manhattan distance overflows")]
+    fn test_manhattan_overflow() {
+        Point2::new(0u8, 0).manhattan(Point2::new(200, 200));
+    }
+
+    #[test]
+    #[should_panic(expected = "chebyshev distance overflows")]
+    fn test_chebyshev_overflow() {
+        Point3::new(-100i8, 0, 0).chebyshev(Point3::new(100, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_point() {
+        assert_eq!("498,4".parse(), Ok(Point2::new(498, 4)));
+        assert_eq!("-1, 3".parse(), Ok(Point2::new(-1i32, 3)));
+        assert!("1;3".parse::<Point2<i32>>().is_err());
+        assert!("1,x".parse::<Point2<i32>>().is_err());
+    }
+
+    #[test]
+    fn test_directions() {
+        use Direction::*;
+
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Up.turn_left(), Left);
+        assert_eq!(Left.turn_right().turn_right(), Right);
+        assert_eq!(Down.opposite(), Up);
+        assert_eq!(Direction::ALL.map(<(isize, isize)>::from), crate::grid::ORTHOGONAL);
+        assert_eq!(Point2::new(2i32, 2).step(Up).step(Left), Point2::new(1, 1));
+
+        for (text, direction) in [("U", Up), ("v", Down), ("<", Left), ("E", Right), ("N", Up)] {
+            assert_eq!(text.parse(), Ok(direction));
+        }
+        assert!("UD".parse::<Direction>().is_err());
+        assert!(Direction::try_from('x').is_err());
+    }
+
+    #[test]
+    fn test_point3() {
+        let a: Point3<i64> = "2,2,2".parse().unwrap();
+        let b: Point3<i64> = "1, 2, -5".parse().unwrap();
+        assert_eq!(a - b, Point3::new(1, 0, 7));
+        assert_eq!(a.manhattan(b), 8);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!(-(b * 2), Point3::new(-2, -4, 10));
+        assert!("1,2".parse::<Point3<i64>>().is_err());
+
+        let faces: Vec<_> = a.neighbors6().collect();
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|&p| p.manhattan(a) == 1));
+
+        let around: Vec<_> = a.neighbors26().collect();
+        assert_eq!(around.len(), 26);
+        assert!(around.iter().all(|&p| p != a && p.chebyshev(a) == 1));
+    }
+}
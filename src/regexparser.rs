@@ -1,46 +1,387 @@
+//! Regex driven parsing: `reparse!((T, ...), regex, input)` parses each
+//! capture group of the first match into the matching tuple slot.
+//!
+//! ```
+//! use aoc_rs::reparse;
+//!
+//! let (lo, hi, letter) = reparse!((u32, u32, char), r"(\d+)-(\d+) (\w)", "1-3 a");
+//! assert_eq!((lo, hi, letter), (1, 3, 'a'));
+//! ```
+//!
+//! The regex is checked when building, so neither a bad pattern nor a type
+//! list that doesn't line up with the capture groups compiles:
+//!
+//! ```compile_fail
+//! let (lo, hi) = aoc_rs::reparse!((u32, u32), r"(\d+)-\d+", "1-3");
+//! ```
+//!
+//! ```compile_fail
+//! let (lo,) = aoc_rs::reparse!((u32,), r"(\d+", "1");
+//! ```
+//!
+//! [`reparse_into!`](crate::reparse_into) fills a `#[derive(FromCaptures)]`
+//! struct from named groups instead, and likewise won't build with a regex
+//! that lacks one of them:
+//!
+//! ```compile_fail
+//! use aoc_rs::regexparser::FromCaptures;
+//!
+//! #[derive(FromCaptures)]
+//! struct Range {
+//!     lo: u32,
+//!     hi: u32,
+//! }
+//!
+//! let range = aoc_rs::reparse_into!(Range, r"(?P<lo>\d+)-\d+", "1-3");
+//! ```
 
+use std::{error::Error, fmt, str::FromStr};
+
+pub use regex::{Captures, Regex};
+pub use aoc_rs_macros::FromCaptures;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReparseErrorKind {
+    NoMatch,
+    MissingGroup { group: usize },
+    MissingName { name: String },
+    NotCaptured { group: usize },
+    Parse { group: usize, text: String, error: String },
+}
+
+/// Why a [`try_reparse!`](crate::try_reparse) call failed, along with the
+/// regex and input involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReparseError {
+    pub regex: String,
+    pub input: String,
+    /// The 1-based line of a multi-line input that `input` came from.
+    pub line: Option<usize>,
+    pub kind: ReparseErrorKind,
+}
+
+impl ReparseError {
+    pub fn new(regex: &str, input: &str, kind: ReparseErrorKind) -> Self {
+        Self { regex: regex.to_owned(), input: input.to_owned(), line: None, kind }
+    }
+
+    pub fn on_line(self, line: usize) -> Self {
+        Self { line: Some(line), ..self }
+    }
+}
+
+impl fmt::Display for ReparseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        match &self.kind {
+            ReparseErrorKind::NoMatch => {
+                write!(f, "regex \"{}\" did not match \"{}\"", self.regex, self.input)
+            }
+            ReparseErrorKind::MissingGroup { group } => {
+                write!(f, "regex \"{}\" has no capture group {}", self.regex, group)
+            }
+            ReparseErrorKind::MissingName { name } => {
+                write!(f, "regex \"{}\" has no capture group named {}", self.regex, name)
+            }
+            ReparseErrorKind::NotCaptured { group } => {
+                write!(f, "group {} of regex \"{}\" captured nothing in \"{}\"", group, self.regex, self.input)
+            }
+            ReparseErrorKind::Parse { group, text, error } => write!(
+                f,
+                "group {} of regex \"{}\" captured \"{}\" in \"{}\", which failed to parse: {}",
+                group, self.regex, text, self.input, error,
+            ),
+        }
+    }
+}
+
+impl Error for ReparseError {}
+
+/// The text of capture group `group`, or `None` if it didn't participate in
+/// the match.
+fn group_text<'t>(captures: &Captures<'t>, group: usize, regex: &str, input: &str) -> Result<Option<&'t str>, ReparseError> {
+    if group >= captures.len() {
+        return Err(ReparseError::new(regex, input, ReparseErrorKind::MissingGroup { group }));
+    }
+    Ok(captures.get(group).map(|m| m.as_str()))
+}
+
+fn parse_text<T>(text: &str, group: usize, regex: &str, input: &str) -> Result<T, ReparseError>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    text.parse().map_err(|e| ReparseError::new(regex, input, ReparseErrorKind::Parse {
+        group,
+        text: text.to_owned(),
+        error: format!("{e:?}"),
+    }))
+}
+
+/// Parses capture group `group` of `captures` with `FromStr`.
+pub fn parse_group<T>(captures: &Captures, group: usize, regex: &str, input: &str) -> Result<T, ReparseError>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    let text = group_text(captures, group, regex, input)?
+        .ok_or_else(|| ReparseError::new(regex, input, ReparseErrorKind::NotCaptured { group }))?;
+
+    parse_text(text, group, regex, input)
+}
+
+/// Like [`parse_group`], but a group that didn't participate gives `None`.
+pub fn parse_optional<T>(captures: &Captures, group: usize, regex: &str, input: &str) -> Result<Option<T>, ReparseError>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    group_text(captures, group, regex, input)?
+        .map(|text| parse_text(text, group, regex, input))
+        .transpose()
+}
+
+/// Splits capture group `group` on `separator` and parses each trimmed,
+/// non-empty piece, so `"79, 98"` split on `","` gives `[79, 98]`.
+pub fn parse_list<T>(captures: &Captures, group: usize, separator: &str, regex: &str, input: &str) -> Result<Vec<T>, ReparseError>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    let text = group_text(captures, group, regex, input)?
+        .ok_or_else(|| ReparseError::new(regex, input, ReparseErrorKind::NotCaptured { group }))?;
+
+    text.split(separator)
+        .map(str::trim)
+        .filter(|piece| !piece.is_empty())
+        .map(|piece| parse_text(piece, group, regex, input))
+        .collect()
+}
+
+/// Parses the capture group named `name` with `FromStr`.
+pub fn parse_named<T>(captures: &Captures, regex: &Regex, name: &str, input: &str) -> Result<T, ReparseError>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    let group = regex
+        .capture_names()
+        .position(|n| n == Some(name))
+        .ok_or_else(|| ReparseError::new(regex.as_str(), input, ReparseErrorKind::MissingName { name: name.to_owned() }))?;
+
+    parse_group(captures, group, regex.as_str(), input)
+}
+
+/// Types built from the named capture groups of a regex match, usually via
+/// `#[derive(FromCaptures)]`, for use with [`reparse_into!`](crate::reparse_into).
+pub trait FromCaptures: Sized {
+    /// The named groups `from_captures` reads, which `reparse_into!` checks
+    /// the regex has when building.
+    const GROUPS: &'static [&'static str];
+
+    fn from_captures(captures: &Captures, regex: &Regex, input: &str) -> Result<Self, ReparseError>;
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether every name in `wanted` is among `groups`, for the compile time
+/// check in [`reparse_into!`](crate::reparse_into).
+#[doc(hidden)]
+pub const fn has_groups(wanted: &[&str], groups: &[&str]) -> bool {
+    let mut i = 0;
+    while i < wanted.len() {
+        let mut j = 0;
+        while j < groups.len() && !str_eq(wanted[i], groups[j]) {
+            j += 1;
+        }
+        if j == groups.len() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[doc(hidden)]
 #[macro_export]
-macro_rules! reparse {
-    ( ( $($T:ty),* ), $regex:literal, $string:expr) => {
+macro_rules! __try_reparse_into {
+    ( $T:ty, $regex:literal, $string:expr) => {
         {
             use once_cell::sync::Lazy;
             use regex::Regex;
+            use $crate::regexparser::{FromCaptures, ReparseError, ReparseErrorKind};
 
             static REGEX: Lazy<Regex> = Lazy::new(|| {
                 Regex::new($regex)
                     .expect("Regex did not compile")
             });
 
-            let ca;
-            let mut iter;
+            let input: &str = $string;
 
-            if let Some(c) = REGEX.captures($string) {
-                ca = c;
-                iter = ca.iter();
-                iter.next();
-            } else {
-                panic!("Regex \"{}\" did not match string \"{}\"", $regex, $string);
+            match REGEX.captures(input) {
+                Some(captures) => <$T as FromCaptures>::from_captures(&captures, &REGEX, input),
+                None => Err(ReparseError::new($regex, input, ReparseErrorKind::NoMatch)),
             }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reparse_into {
+    ( $T:ty, $regex:literal, $string:expr) => {
+        $crate::__try_reparse_into!($T, $regex, $string).unwrap_or_else(|e| panic!("{}", e))
+    };
+}
+
+/// Parses the slots of a [`reparse!`](crate::reparse) type list one capture
+/// group at a time. `Option<T>` accepts a group that didn't participate,
+/// `Vec<T>` splits the group on commas and `Vec<T, "sep">` on `sep`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reparse_slots {
+    ($captures:ident, $group:ident, $regex:expr, $input:ident; [$($parsed:expr,)*]) => {
+        ($($parsed,)*)
+    };
+    ($captures:ident, $group:ident, $regex:expr, $input:ident; [$($parsed:expr,)*] Option<$T:ty> $(, $($rest:tt)*)?) => {
+        $crate::__reparse_slots!($captures, $group, $regex, $input; [$($parsed,)* {
+            $group += 1;
+            $crate::regexparser::parse_optional::<$T>(&$captures, $group, $regex, $input)?
+        },] $($($rest)*)?)
+    };
+    ($captures:ident, $group:ident, $regex:expr, $input:ident; [$($parsed:expr,)*] Vec<$T:ty, $separator:literal> $(, $($rest:tt)*)?) => {
+        $crate::__reparse_slots!($captures, $group, $regex, $input; [$($parsed,)* {
+            $group += 1;
+            $crate::regexparser::parse_list::<$T>(&$captures, $group, $separator, $regex, $input)?
+        },] $($($rest)*)?)
+    };
+    ($captures:ident, $group:ident, $regex:expr, $input:ident; [$($parsed:expr,)*] Vec<$T:ty> $(, $($rest:tt)*)?) => {
+        $crate::__reparse_slots!($captures, $group, $regex, $input; [$($parsed,)* {
+            $group += 1;
+            $crate::regexparser::parse_list::<$T>(&$captures, $group, ",", $regex, $input)?
+        },] $($($rest)*)?)
+    };
+    ($captures:ident, $group:ident, $regex:expr, $input:ident; [$($parsed:expr,)*] $T:ty $(, $($rest:tt)*)?) => {
+        $crate::__reparse_slots!($captures, $group, $regex, $input; [$($parsed,)* {
+            $group += 1;
+            $crate::regexparser::parse_group::<$T>(&$captures, $group, $regex, $input)?
+        },] $($($rest)*)?)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __try_reparse {
+    ( ( $($slots:tt)* ), $regex:literal, $string:expr) => {
+        {
+            use once_cell::sync::Lazy;
+            use regex::Regex;
+            use $crate::regexparser::{ReparseError, ReparseErrorKind};
+
+            static REGEX: Lazy<Regex> = Lazy::new(|| {
+                Regex::new($regex)
+                    .expect("Regex did not compile")
+            });
+
+            let input: &str = $string;
+
+            (|| -> Result<_, ReparseError> {
+                let captures = REGEX
+                    .captures(input)
+                    .ok_or_else(|| ReparseError::new($regex, input, ReparseErrorKind::NoMatch))?;
+
+                #[allow(unused_mut, unused_variables)]
+                let mut group = 0;
+
+                Ok($crate::__reparse_slots!(captures, group, $regex, input; [] $($slots)*))
+            })()
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reparse {
+    ( ( $($slots:tt)* ), $regex:literal, $string:expr) => {
+        $crate::__try_reparse!(($($slots)*), $regex, $string).unwrap_or_else(|e| panic!("{}", e))
+    };
+}
 
-            (
-                $(iter
-                    .next()
-                    .expect("Not enough capture groups")
-                    .expect("Nothing captured")
-                    .as_str()
-                    .parse::<$T>()
-                    .expect("Parse failed")
-                ,)*
-            )
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __try_reparse_all {
+    ( ( $($slots:tt)* ), $regex:literal, $string:expr) => {
+        {
+            use once_cell::sync::Lazy;
+            use regex::Regex;
+            use $crate::regexparser::ReparseError;
+
+            static REGEX: Lazy<Regex> = Lazy::new(|| {
+                Regex::new($regex)
+                    .expect("Regex did not compile")
+            });
+
+            let input: &str = $string;
+
+            REGEX.captures_iter(input).map(move |captures| -> Result<_, ReparseError> {
+                #[allow(unused_mut, unused_variables)]
+                let mut group = 0;
+
+                Ok($crate::__reparse_slots!(captures, group, $regex, input; [] $($slots)*))
+            })
         }
     };
-    ( ( $($T:ty),+ , ), $regex:expr, $string:expr) => {
-        reparse!(($($T),*), $regex, $string)
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reparse_all {
+    ( ( $($slots:tt)* ), $regex:literal, $string:expr) => {
+        $crate::__try_reparse_all!(($($slots)*), $regex, $string).map(|r| r.unwrap_or_else(|e| panic!("{}", e)))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reparse_lines {
+    ( $skip_blank:literal, ( $($slots:tt)* ), $regex:literal, $string:expr) => {
+        {
+            let input: &str = $string;
+
+            input
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !($skip_blank && line.trim().is_empty()))
+                .map(|(i, line)| {
+                    $crate::__try_reparse!(($($slots)*), $regex, line).map_err(|e| e.on_line(i + 1))
+                })
+                .collect::<Result<Vec<_>, $crate::regexparser::ReparseError>>()
+        }
     };
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{reparse, reparse_all, reparse_into, reparse_lines, try_reparse, try_reparse_all, try_reparse_into};
+
     #[test]
     fn test_regex_parse() {
         assert_eq!(reparse!((i32,), r"(\d+)", "1"), (1i32,));
@@ -55,4 +396,170 @@ mod tests {
             assert_eq!(reparse!((String, i32, i32), r"^(\w+): (\d+) -> (\d+)$", test), result);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_try_reparse_errors() {
+        let error = try_reparse!((i32, i32), r"^(\d+)-(\d+)$", "1 2").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::NoMatch);
+        assert_eq!(error.regex, r"^(\d+)-(\d+)$");
+        assert_eq!(error.input, "1 2");
+
+        let error = try_reparse!((u8, u8), r"^(\d+)-(\d+)$", "1-300").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::Parse {
+            group: 2,
+            text: "300".into(),
+            error: "ParseIntError { kind: PosOverflow }".into(),
+        });
+        assert!(error.to_string().contains("group 2"));
+
+        // reparse! rejects this at compile time, the unchecked macro can't
+        let error = crate::__try_reparse!((i32, i32), r"^(\d+)$", "1").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::MissingGroup { group: 2 });
+
+        let error = try_reparse!((i32, i32), r"^(\d+)|(x)$", "1").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::NotCaptured { group: 2 });
+    }
+
+    #[test]
+    #[should_panic(expected = "group 1")]
+    fn test_reparse_panics_with_group() {
+        reparse!((i32,), r"(\w+)", "abc");
+    }
+
+    #[test]
+    fn test_try_reparse_in_from_str() {
+        #[derive(Debug, PartialEq)]
+        struct Assignment(i64, i64);
+
+        impl FromStr for Assignment {
+            type Err = ReparseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (a, b) = try_reparse!((i64, i64), r"^(\d+)-(\d+)$", s)?;
+                Ok(Self(a, b))
+            }
+        }
+
+        assert_eq!("2-4".parse(), Ok(Assignment(2, 4)));
+        assert!("2-".parse::<Assignment>().is_err());
+    }
+
+    #[derive(Debug, PartialEq, FromCaptures)]
+    struct Move {
+        count: usize,
+        from: usize,
+        #[reparse(group = "dest")]
+        to: usize,
+    }
+
+    #[test]
+    fn test_reparse_into() {
+        let m = reparse_into!(Move, r"move (?P<count>\d+) from (?P<from>\d+) to (?P<dest>\d+)", "move 3 from 1 to 2");
+        assert_eq!(m, Move { count: 3, from: 1, to: 2 });
+
+        // group order in the regex doesn't matter
+        let m = reparse_into!(Move, r"(?P<dest>\d+) <- (?P<count>\d+) <- (?P<from>\d+)", "2 <- 3 <- 1");
+        assert_eq!(m, Move { count: 3, from: 1, to: 2 });
+    }
+
+    #[derive(Debug, PartialEq, FromCaptures)]
+    struct Pair<T: FromStr>
+    where
+        T::Err: fmt::Debug,
+    {
+        a: T,
+        b: T,
+    }
+
+    fn pair<T: FromStr>(line: &str) -> Pair<T>
+    where
+        T::Err: fmt::Debug,
+    {
+        reparse_into!(Pair<T>, r"(?P<a>\w+),(?P<b>\w+)", line)
+    }
+
+    #[test]
+    fn test_reparse_into_generic() {
+        assert_eq!(pair::<u8>("1,2"), Pair { a: 1, b: 2 });
+        assert_eq!(pair::<String>("x,y"), Pair { a: "x".to_string(), b: "y".to_string() });
+    }
+
+    #[test]
+    fn test_has_groups() {
+        assert!(has_groups(Move::GROUPS, &["dest", "from", "count", "extra"]));
+        assert!(!has_groups(Move::GROUPS, &["count", "from", "to"]));
+        assert!(has_groups(&[], &[]));
+    }
+
+    #[test]
+    fn test_try_reparse_into_errors() {
+        // a missing group no longer compiles through the public macro
+        let error = crate::__try_reparse_into!(Move, r"(?P<count>\d+) (?P<from>\d+)", "3 1").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::MissingName { name: "dest".into() });
+
+        let error = try_reparse_into!(Move, r"(?P<count>\w+) (?P<from>\d+) (?P<dest>\d+)", "x 1 2").unwrap_err();
+        assert!(matches!(error.kind, ReparseErrorKind::Parse { group: 1, .. }));
+
+        let error = try_reparse_into!(Move, r"(?P<count>\d+) (?P<from>\d+) (?P<dest>\d+)", "1 2").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::NoMatch);
+    }
+
+    #[test]
+    fn test_reparse_all() {
+        let path: Vec<_> = reparse_all!((i32, i32), r"(\d+),(\d+)", "498,4 -> 498,6 -> 496,6").collect();
+        assert_eq!(path, [(498, 4), (498, 6), (496, 6)]);
+
+        assert_eq!(reparse_all!((u8,), r"(\d+)", "none here").count(), 0);
+
+        let mut results = try_reparse_all!((String, u8), r"(\w+)=(\d+)", "a=1 b=300 c=3");
+        assert_eq!(results.next().unwrap().unwrap(), ("a".to_string(), 1));
+        assert!(matches!(results.next().unwrap().unwrap_err().kind, ReparseErrorKind::Parse { group: 2, .. }));
+        assert_eq!(results.next().unwrap().unwrap(), ("c".to_string(), 3));
+        assert!(results.next().is_none());
+    }
+
+    #[test]
+    fn test_optional_groups() {
+        let regex = r"^(\w+)(?: \+(\d+))?$";
+        assert_eq!(reparse!((String, Option<u32>), r"^(\w+)(?: \+(\d+))?$", "bonus +3"), ("bonus".into(), Some(3)));
+        assert_eq!(reparse!((String, Option<u32>), r"^(\w+)(?: \+(\d+))?$", "bonus"), ("bonus".into(), None));
+
+        let error = try_reparse!((String, u32), r"^(\w+)(?: \+(\d+))?$", "bonus").unwrap_err();
+        assert_eq!(error, ReparseError::new(regex, "bonus", ReparseErrorKind::NotCaptured { group: 2 }));
+        let error = try_reparse!((String, Option<u8>), r"^(\w+)(?: \+(\d+))?$", "bonus +300").unwrap_err();
+        assert!(matches!(error.kind, ReparseErrorKind::Parse { group: 2, .. }));
+    }
+
+    #[test]
+    fn test_list_groups() {
+        let (items,) = reparse!((Vec<u64>,), r"Starting items: (.*)", "  Starting items: 79, 98");
+        assert_eq!(items, [79, 98]);
+        let (items,) = reparse!((Vec<u64>,), r"Starting items: (.*)", "  Starting items: ");
+        assert!(items.is_empty());
+
+        let (name, words, count) = reparse!((String, Vec<String, " ">, usize), r"(\w+): ([a-z ]+) (\d+)", "x: a  b c 3");
+        assert_eq!((name.as_str(), words, count), ("x", vec!["a".to_string(), "b".into(), "c".into()], 3));
+
+        let error = try_reparse!((Vec<u8>,), r"(.*)", "1, x").unwrap_err();
+        assert_eq!(error.kind, ReparseErrorKind::Parse {
+            group: 1,
+            text: "x".into(),
+            error: "ParseIntError { kind: InvalidDigit }".into(),
+        });
+    }
+
+    #[test]
+    fn test_reparse_lines() {
+        let input = "1-2\n3-4\n\n5-6\n";
+        let error = reparse_lines!((u32, u32), r"^(\d+)-(\d+)$", input).unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.kind, ReparseErrorKind::NoMatch);
+
+        let pairs = reparse_lines!((u32, u32), r"^(\d+)-(\d+)$", input, skip_blank).unwrap();
+        assert_eq!(pairs, [(1, 2), (3, 4), (5, 6)]);
+
+        let error = reparse_lines!((u32, u32), r"^(\d+)-(\d+)$", "1-2\r\n3-x4", skip_blank).unwrap_err();
+        assert_eq!(error.input, "3-x4");
+        assert_eq!(error.to_string(), r#"line 2: regex "^(\d+)-(\d+)$" did not match "3-x4""#);
+    }
+}
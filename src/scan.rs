@@ -0,0 +1,211 @@
+//! Format-string parsing: `scan!("move {} from {}", input => usize, usize)`
+//! parses each `{}` with `FromStr`. No regex is involved: every placeholder
+//! takes the text up to the next literal segment. As with `format!`, `{{`
+//! and `}}` stand for literal braces.
+//!
+//! ```
+//! use aoc_rs::scan;
+//! let (count, from, to) = scan!("move {} from {} to {}", "move 1 from 2 to 3" => usize, usize, usize).unwrap();
+//! assert_eq!((count, from, to), (1, 2, 3));
+//! ```
+//!
+//! The format is checked when building, so a format without placeholders,
+//! a placeholder count that doesn't match the types, and two placeholders
+//! with nothing between them all fail to compile:
+//!
+//! ```compile_fail
+//! aoc_rs::scan!("done", "done" =>).unwrap();
+//! ```
+//!
+//! ```compile_fail
+//! let (a,) = aoc_rs::scan!("{} {}", "1 2" => usize).unwrap();
+//! ```
+//!
+//! ```compile_fail
+//! let (a, b) = aoc_rs::scan!("{}{}", "12" => usize, usize).unwrap();
+//! ```
+
+use std::str::FromStr;
+
+use crate::parser::{ParseError, ParseErrorKind};
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __scan {
+    ($input:expr => $first:literal $(, $T:ty => $literal:literal)+) => {
+        (|| -> ::std::result::Result<_, $crate::parser::ParseError> {
+            let mut scanner = $crate::scan::Scanner::new($input);
+            scanner.literal($first)?;
+            let values = ($({
+                let value = scanner.field::<$T>($literal)?;
+                scanner.literal($literal)?;
+                value
+            },)+);
+            scanner.finish()?;
+            Ok(values)
+        })()
+    };
+}
+
+/// The runtime half of [`scan!`](crate::scan!). The macro splits the format
+/// when building, so `scan!("move {} from {}", line => u8, u8)` runs
+///
+/// ```
+/// # use aoc_rs::scan::Scanner;
+/// # let line = "move 1 from 2";
+/// let mut scanner = Scanner::new(line);
+/// scanner.literal("move ")?;
+/// let count: u8 = scanner.field(" from ")?;
+/// scanner.literal(" from ")?;
+/// let from: u8 = scanner.field("")?;
+/// scanner.literal("")?;
+/// scanner.finish()?;
+/// # assert_eq!((count, from), (1, 2));
+/// # Ok::<(), aoc_rs::parser::ParseError>(())
+/// ```
+///
+/// with every literal a constant the compiler can specialise the matching for.
+#[derive(Debug)]
+pub struct Scanner<'a> {
+    input: &'a str,
+    at: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, at: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.at..]
+    }
+
+    /// Matches `literal` exactly.
+    #[inline]
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if literal.is_empty() {
+            return Ok(());
+        }
+        match self.rest().strip_prefix(literal) {
+            Some(_) => {
+                self.at += literal.len();
+                Ok(())
+            }
+            None => {
+                let kind = ParseErrorKind::Expected { expected: format!("{literal:?}"), found: self.rest().chars().next() };
+                Err(ParseError::new(self.at, kind))
+            }
+        }
+    }
+
+    /// Parses the text up to the next occurrence of `until`, or to the end
+    /// of the input if `until` is empty, leaving `until` itself unconsumed.
+    #[inline]
+    pub fn field<T: FromStr>(&mut self, until: &str) -> Result<T, ParseError> {
+        let rest = self.rest();
+        let len = if until.is_empty() {
+            rest.len()
+        } else {
+            find(rest, until).ok_or_else(|| {
+                let kind = ParseErrorKind::Expected { expected: format!("{until:?}"), found: rest.chars().next() };
+                ParseError::new(self.at, kind)
+            })?
+        };
+
+        let text = &rest[..len];
+        let value = text.parse().map_err(|_| {
+            let kind = ParseErrorKind::Invalid { expected: std::any::type_name::<T>().to_owned(), text: text.to_owned() };
+            ParseError::new(self.at, kind)
+        })?;
+        self.at += len;
+        Ok(value)
+    }
+
+    pub fn finish(&self) -> Result<(), ParseError> {
+        if self.at < self.input.len() {
+            let kind = ParseErrorKind::Expected { expected: "end of input".to_string(), found: self.rest().chars().next() };
+            return Err(ParseError::new(self.at, kind));
+        }
+        Ok(())
+    }
+}
+
+/// The first occurrence of a non-empty `literal` in `text`. Scanning for
+/// its first byte is much cheaper than building a `str::find` searcher
+/// for every field of every line, and the literals are short.
+#[inline]
+fn find(text: &str, literal: &str) -> Option<usize> {
+    let literal = literal.as_bytes();
+    let text = text.as_bytes();
+    let mut from = 0;
+    while let Some(i) = text[from..].iter().position(|&b| b == literal[0]) {
+        let at = from + i;
+        if text[at..].starts_with(literal) {
+            return Some(at);
+        }
+        from = at + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scan;
+    use crate::parser::ParseErrorKind;
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(scan!("{}", "1" => i32), Ok((1,)));
+        assert_eq!(scan!("move {} from {} to {}", "move 1 from 2 to 3" => usize, usize, usize), Ok((1, 2, 3)));
+        assert_eq!(
+            scan!("{}: {} -> {}", "Santa: 1 -> 2" => String, i32, i32),
+            Ok(("Santa".to_string(), 1, 2)),
+        );
+        assert_eq!(scan!("{}-{},{}-{}", "2-4,6-8" => i64, i64, i64, i64), Ok((2, 4, 6, 8)));
+        assert_eq!(scan!("[{}]", "[ok]" => String), Ok(("ok".to_string(),)));
+        assert_eq!(scan!("{{{}}}", "{5}" => u8), Ok((5,)));
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        let error = scan!("move {} from {}", "move x from 2" => usize, usize).unwrap_err();
+        assert_eq!(error.at, 5);
+        assert!(matches!(error.kind, ParseErrorKind::Invalid { .. }));
+
+        assert_eq!(scan!("move {} from {}", "mov 1 from 2" => usize, usize).unwrap_err().at, 0);
+        let error = scan!("{} to {}", "1 from 2" => usize, usize).unwrap_err();
+        assert_eq!(error.at, 0);
+        assert_eq!(error.kind, ParseErrorKind::Expected { expected: "\" to \"".to_string(), found: Some('1') });
+        assert_eq!(scan!("<{}>", "<1>>" => usize).unwrap_err().at, 3);
+        assert_eq!(scan!("{} to {}", "1 tx 2 to" => usize, usize).unwrap_err().at, 0);
+        assert_eq!(scan!("{}é{}", "1é2" => usize, usize), Ok((1, 2)));
+    }
+
+    /// Run with `cargo test --release -- --ignored`. Compares the best of
+    /// several runs, as single runs are noisy.
+    #[test]
+    #[ignore]
+    fn bench_scan_vs_reparse() {
+        use std::time::{Duration, Instant};
+
+        let lines: Vec<String> = (0..200_000).map(|i| format!("move {} from {} to {}", i % 50, i % 9, i % 7)).collect();
+
+        let best = |f: &dyn Fn(&str) -> (usize, usize, usize)| {
+            let mut best = Duration::MAX;
+            let mut total = 0;
+            for _ in 0..5 {
+                let start = Instant::now();
+                total = lines.iter().map(|line| f(line)).map(|(count, from, to)| count + from + to).sum::<usize>();
+                best = best.min(start.elapsed());
+            }
+            (best, total)
+        };
+
+        let (scan_time, scanned) = best(&|line| scan!("move {} from {} to {}", line => usize, usize, usize).unwrap());
+        let (reparse_time, reparsed) =
+            best(&|line| crate::reparse!((usize, usize, usize), r"move (\d+) from (\d+) to (\d+)", line));
+
+        assert_eq!(scanned, reparsed);
+        assert!(scan_time < reparse_time, "scan! took {scan_time:?}, reparse! took {reparse_time:?}");
+    }
+}
@@ -0,0 +1,246 @@
+//! Breadth-first, Dijkstra and A* search over graphs given as neighbour
+//! closures. Every search takes any number of start nodes and a goal
+//! predicate; it stops at the first goal reached, or explores everything
+//! reachable when the goal is `|_| false`.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The distances a search found, and the tree of shortest paths behind them.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Self { distances: HashMap::new(), parents: HashMap::new(), goal: None }
+    }
+
+    /// The goal node the search stopped at, if it found one.
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// The distance to the goal node.
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every node reached along with its distance. When the search stopped
+    /// early some of these may not be final.
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    /// The nodes from the nearest start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal node.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth-first search, where every edge costs one step.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let distance = search.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm, for neighbours given with non-negative edge costs.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbors, |_| C::default(), goal)
+}
+
+/// A* search: Dijkstra's algorithm guided by `heuristic`, an estimate of the
+/// remaining cost to a goal. The heuristic must never overestimate, and
+/// paths are only guaranteed shortest when it's also consistent.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new();
+    // nodes live in `open` so the heap needn't order them
+    let mut open = vec![];
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), C::default());
+            heap.push(Reverse((heuristic(&start), open.len())));
+            open.push((start, C::default()));
+        }
+    }
+
+    while let Some(Reverse((_, id))) = heap.pop() {
+        let (node, cost) = open[id].clone();
+        if search.distances[&node] < cost {
+            // a cheaper way here was found after this entry was queued
+            continue;
+        }
+        if goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if search.distances.get(&next).is_none_or(|&known| next_cost < known) {
+                search.distances.insert(next.clone(), next_cost);
+                search.parents.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), open.len())));
+                open.push((next, next_cost));
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#.E.
+.####.#.
+........";
+
+    fn maze() -> (Grid<char>, (usize, usize), (usize, usize)) {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let find = |c| grid.positions().find(|&p| grid[p] == c).unwrap();
+        let (start, end) = (find('S'), find('E'));
+        (grid, start, end)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, end) = maze();
+        let open = |p: &(usize, usize)| grid.neighbors4(*p).filter(|&q| grid[q] != '#').collect::<Vec<_>>();
+
+        let search = bfs([start], open, |&p| p == end);
+        assert_eq!(search.goal(), Some(&end));
+        assert_eq!(search.goal_distance(), Some(12));
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), (start, end));
+        assert!(path.windows(2).all(|w| grid.neighbors4(w[0]).any(|q| q == w[1])));
+
+        let everything = bfs([start], open, |_| false);
+        assert_eq!(everything.goal(), None);
+        assert_eq!(everything.distances().len(), grid.cells().iter().filter(|&&c| c != '#').count());
+        assert_eq!(everything.distance(&(7, 0)), Some(11));
+        assert_eq!(everything.distance(&(2, 0)), None);
+        assert_eq!(everything.path_to(&start), Some(vec![start]));
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let search = bfs([0, 10], |&n: &i32| [n - 1, n + 1], |&n| n == 7);
+        assert_eq!(search.goal_distance(), Some(3));
+        assert_eq!(search.path(), Some(vec![10, 9, 8, 7]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // the direct edge is more expensive than the detour
+        let edges = |&n: &char| -> Vec<(char, u32)> {
+            match n {
+                'a' => vec![('b', 7), ('c', 2)],
+                'c' => vec![('d', 2), ('b', 9)],
+                'd' => vec![('b', 1)],
+                _ => vec![],
+            }
+        };
+
+        let search = dijkstra(['a'], edges, |&n| n == 'b');
+        assert_eq!(search.goal_distance(), Some(5));
+        assert_eq!(search.path(), Some(vec!['a', 'c', 'd', 'b']));
+        assert_eq!(dijkstra(['a'], edges, |&n| n == 'z').goal(), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let (grid, start, end) = maze();
+        let open = |p: &(usize, usize)| {
+            grid.neighbors4(*p).filter(|&q| grid[q] != '#').map(|q| (q, 1)).collect::<Vec<_>>()
+        };
+        let manhattan = |p: &(usize, usize)| p.0.abs_diff(end.0) + p.1.abs_diff(end.1);
+
+        let search = astar([start], open, manhattan, |&p| p == end);
+        assert_eq!(search.goal_distance(), Some(12));
+        assert_eq!(search.path().unwrap().len(), 13);
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::{hash_map, HashMap},
+    ops::RangeInclusive,
+};
+
+use crate::{
+    grid::{ADJACENT, ORTHOGONAL},
+    point::Point2,
+};
+
+/// Which way increasing `y` points when rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YAxis {
+    /// Screen coordinates: the first line printed is the smallest `y`.
+    Down,
+    /// Maths coordinates: the first line printed is the largest `y`.
+    Up,
+}
+
+/// An unbounded grid holding only the cells that have been set, keyed by
+/// [`Point2`], that keeps track of the bounding box of its cells. Positions
+/// can be passed as `Point2`s or `(x, y)` tuples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<i64>, T>,
+    bounds: Bounds,
+}
+
+type Bounds = Option<(Point2<i64>, Point2<i64>)>;
+
+fn grow(bounds: Bounds, at: Point2<i64>) -> Bounds {
+    Some(match bounds {
+        Some((min, max)) => {
+            (Point2::new(min.x.min(at.x), min.y.min(at.y)), Point2::new(max.x.max(at.x), max.y.max(at.y)))
+        }
+        None => (at, at),
+    })
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn insert(&mut self, at: impl Into<Point2<i64>>, value: T) -> Option<T> {
+        let at = at.into();
+        self.bounds = grow(self.bounds, at);
+        self.cells.insert(at, value)
+    }
+
+    pub fn remove(&mut self, at: impl Into<Point2<i64>>) -> Option<T> {
+        let at = at.into();
+        let value = self.cells.remove(&at)?;
+
+        // only a cell on the edge of the bounding box can shrink it
+        if let Some((min, max)) = self.bounds {
+            if at.x == min.x || at.x == max.x || at.y == min.y || at.y == max.y {
+                self.bounds = self.cells.keys().fold(None, |bounds, &at| grow(bounds, at));
+            }
+        }
+
+        Some(value)
+    }
+
+    pub fn get(&self, at: impl Into<Point2<i64>>) -> Option<&T> {
+        self.cells.get(&at.into())
+    }
+
+    pub fn get_mut(&mut self, at: impl Into<Point2<i64>>) -> Option<&mut T> {
+        self.cells.get_mut(&at.into())
+    }
+
+    pub fn contains(&self, at: impl Into<Point2<i64>>) -> bool {
+        self.cells.contains_key(&at.into())
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Point2<i64>, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> hash_map::Keys<'_, Point2<i64>, T> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, Point2<i64>, T> {
+        self.cells.values()
+    }
+
+    /// The smallest and largest `x` and `y` of any cell, or `None` when
+    /// empty.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn min_x(&self) -> Option<i64> {
+        self.bounds.map(|(min, _)| min.x)
+    }
+
+    pub fn max_x(&self) -> Option<i64> {
+        self.bounds.map(|(_, max)| max.x)
+    }
+
+    pub fn min_y(&self) -> Option<i64> {
+        self.bounds.map(|(min, _)| min.y)
+    }
+
+    pub fn max_y(&self) -> Option<i64> {
+        self.bounds.map(|(_, max)| max.y)
+    }
+
+    /// The occupied cells up, right, down and left of `at`.
+    pub fn neighbors4(&self, at: impl Into<Point2<i64>>) -> impl Iterator<Item = (Point2<i64>, &T)> + '_ {
+        let at = at.into();
+        ORTHOGONAL.into_iter().filter_map(move |(dx, dy)| {
+            let at = at + Point2::new(dx as i64, dy as i64);
+            self.get(at).map(|value| (at, value))
+        })
+    }
+
+    /// The occupied cells among the eight around `at`.
+    pub fn neighbors8(&self, at: impl Into<Point2<i64>>) -> impl Iterator<Item = (Point2<i64>, &T)> + '_ {
+        let at = at.into();
+        ADJACENT.into_iter().filter_map(move |(dx, dy)| {
+            let at = at + Point2::new(dx as i64, dy as i64);
+            self.get(at).map(|value| (at, value))
+        })
+    }
+
+    /// Draws the bounding box one line per row, mapping each cell (or
+    /// `None` for an empty one) to a character.
+    pub fn render(&self, y_axis: YAxis, cell: impl FnMut(Option<&T>) -> char) -> String {
+        match self.bounds {
+            Some((min, max)) => self.render_region(min.x..=max.x, min.y..=max.y, y_axis, cell),
+            None => String::new(),
+        }
+    }
+
+    /// Like [`render`](Self::render), but for the given region whether or
+    /// not it's occupied.
+    pub fn render_region(
+        &self,
+        xs: RangeInclusive<i64>,
+        ys: RangeInclusive<i64>,
+        y_axis: YAxis,
+        mut cell: impl FnMut(Option<&T>) -> char,
+    ) -> String {
+        let mut out = String::new();
+        let mut line = |y| {
+            for x in xs.clone() {
+                out.push(cell(self.get((x, y))));
+            }
+            out.push('\n');
+        };
+
+        match y_axis {
+            YAxis::Down => ys.for_each(&mut line),
+            YAxis::Up => ys.rev().for_each(&mut line),
+        }
+        out
+    }
+}
+
+impl<T> FromIterator<(Point2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2<i64>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point2<i64>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point2<i64>, T)>>(&mut self, iter: I) {
+        for (at, value) in iter {
+            self.insert(at, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert((2, -1), 'a');
+        grid.insert((-3, 4), 'b');
+        grid.insert((0, 0), 'c');
+        assert_eq!(grid.bounds(), Some((Point2::new(-3, -1), Point2::new(2, 4))));
+        assert_eq!((grid.min_x(), grid.max_y()), (Some(-3), Some(4)));
+
+        grid.remove(Point2::new(0, 0));
+        assert_eq!(grid.bounds(), Some((Point2::new(-3, -1), Point2::new(2, 4))));
+        grid.remove((-3, 4));
+        assert_eq!(grid.bounds(), Some((Point2::new(2, -1), Point2::new(2, -1))));
+        assert_eq!(grid.remove((-3, 4)), None);
+        grid.remove((2, -1));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let cells = [((0, 0), 1), ((1, 0), 2), ((1, 1), 3), ((5, 5), 4)];
+        let grid: SparseGrid<u8> = cells.into_iter().map(|(at, value)| (Point2::from(at), value)).collect();
+        assert_eq!(grid.neighbors4((0, 1)).collect::<Vec<_>>(), [(Point2::new(0, 0), &1), (Point2::new(1, 1), &3)]);
+        assert_eq!(grid.neighbors8((0, 1)).count(), 3);
+        assert_eq!(grid.neighbors8((3, 3)).count(), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: SparseGrid<()> = [(0, 0), (2, 0), (1, 1)].into_iter().map(|p| (Point2::from(p), ())).collect();
+        let cell = |c: Option<&()>| if c.is_some() { '#' } else { '.' };
+
+        assert_eq!(grid.render(YAxis::Down, cell), "#.#\n.#.\n");
+        assert_eq!(grid.render(YAxis::Up, cell), ".#.\n#.#\n");
+        assert_eq!(grid.render_region(-1..=1, 0..=0, YAxis::Down, cell), ".#.\n");
+        assert_eq!(SparseGrid::<()>::new().render(YAxis::Down, cell), "");
+    }
+}
@@ -0,0 +1,904 @@
+use std::{io::{self, Read}, str::FromStr};
+
+use crate::parser::{Integer, ParseError, ParseErrorKind, Parser, Span, Spanned};
+
+const CHUNK: usize = 8 * 1024;
+const LOOKAHEAD: usize = CHUNK;
+
+/// Whether `b` continues a multi-byte UTF-8 character rather than starting one.
+fn is_continuation(b: u8) -> bool {
+    b & 0xc0 == 0x80
+}
+
+/// A [`Parser`] over any `io::Read`, for inputs too large to hold in memory.
+///
+/// Input is read lazily, and each primitive runs the ordinary `Parser` over
+/// the rest of the current line as buffered, so tokens behave exactly as
+/// they do there. Lines may be any length, but a single token (a number, or
+/// a value for `parse_until`) must fit in 8 KiB. Consumed input is
+/// discarded once it falls more than `window` bytes behind the cursor, or
+/// behind the start of the outermost `attempt` still running, so the
+/// combinators can always rewind. Checkpoints taken by hand can only be
+/// restored while they are still inside the window.
+///
+/// The methods mirror `Parser`'s, with these differences:
+///
+/// - `word`, `str_until`, `identifier`, `take`, `rest` and `line` return
+///   owned `String`s, since the buffer they come from is reused. `word`,
+///   `str_until`, `rest` and `line` return them in a `Result`, failing if
+///   they stop at invalid UTF-8 or at a failed read rather than a delimiter.
+/// - `peek`, `done` and `expected` take `&mut self`, as they may need to read.
+/// - `parse_until(Some(delim))` doesn't look past the end of the current
+///   line. `parse_until(None)` parses the rest of the stream, as in `Parser`.
+/// - Checkpoints are [`StreamCheckpoint`]s, and `alt` takes
+///   [`StreamAlternative`]s.
+/// - The byte-level methods, grids and the `*_spanned` shorthands are left
+///   out; use [`parse_line`](StreamParser::parse_line) or
+///   [`spanned`](StreamParser::spanned) for those.
+///
+/// Offsets in errors and checkpoints count bytes from the start of the
+/// stream. A read error ends the stream and is reported by `expect_done`
+/// and by any of the above that runs into it.
+#[derive(Debug)]
+pub struct StreamParser<R> {
+    reader: R,
+    buf: Vec<u8>,
+    at: usize,
+    base: usize,
+    window: usize,
+    eof: bool,
+    error: Option<io::Error>,
+    view: Option<View>,
+    /// Stream offsets of the `attempt`s in progress, which must stay buffered.
+    pins: Vec<usize>,
+}
+
+/// The buffered bytes `start..end`, running to the end of their line or for
+/// `2 * LOOKAHEAD` bytes, checked as UTF-8 once so that consecutive tokens
+/// on a long line don't each rescan them. Any `fill` drops the view, as it
+/// may move or extend the buffer.
+#[derive(Debug)]
+struct View {
+    start: usize,
+    end: usize,
+    /// Whether the view reaches the end of the line or of the stream.
+    complete: bool,
+}
+
+/// A saved stream position, see [`StreamParser::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamCheckpoint(usize);
+
+impl StreamCheckpoint {
+    pub fn position(self) -> usize {
+        self.0
+    }
+}
+
+/// One branch of [`StreamParser::alt`].
+pub type StreamAlternative<'f, R, T> = dyn FnMut(&mut StreamParser<R>) -> Result<T, ParseError> + 'f;
+
+impl<R: Read> StreamParser<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_window(reader, 64 * 1024)
+    }
+
+    pub fn with_window(reader: R, window: usize) -> Self {
+        Self { reader, buf: Vec::new(), at: 0, base: 0, window, eof: false, error: None, view: None, pins: Vec::new() }
+    }
+
+    /// Reads another chunk, first dropping input that has fallen out of the
+    /// window. Returns false at the end of the stream.
+    fn fill(&mut self) -> bool {
+        if self.eof {
+            return false;
+        }
+        self.view = None;
+
+        // compacting only once a whole window's worth can go keeps the copying
+        // amortised
+        let keep = self.pins.iter().min().map_or(self.at, |&pin| pin - self.base);
+        let discard = keep.saturating_sub(self.window);
+        if discard >= self.window.max(CHUNK) {
+            self.buf.drain(..discard);
+            self.at -= discard;
+            self.base += discard;
+        }
+
+        let len = self.buf.len();
+        self.buf.resize(len + CHUNK, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.error = Some(e);
+                    break 0;
+                }
+            }
+        };
+        self.buf.truncate(len + read);
+        self.eof = read == 0;
+        read > 0
+    }
+
+    /// Buffers at least `n` bytes past the cursor, if the stream has them.
+    fn fill_to(&mut self, n: usize) {
+        while self.buf.len() - self.at < n && self.fill() {}
+    }
+
+    /// Buffers up to and including the next `\n`, or at least `2 *
+    /// LOOKAHEAD` bytes if the line is longer than that.
+    fn fill_line(&mut self) {
+        let mut searched = 0;
+        while !self.buf[self.at + searched..].contains(&b'\n') && self.buf.len() - self.at < 2 * LOOKAHEAD {
+            searched = self.buf.len() - self.at;
+            if !self.fill() {
+                break;
+            }
+        }
+    }
+
+    /// The text from the cursor up to the end of the line, or at least
+    /// `LOOKAHEAD` bytes of it. The view is only rebuilt once the cursor
+    /// leaves it or gets within `LOOKAHEAD` of its cut-off end.
+    fn line_view(&mut self) -> &str {
+        let reusable = self.view.as_ref().is_some_and(|view| {
+            self.at >= view.start
+                && self.at < view.end
+                && !is_continuation(self.buf[self.at])
+                && (view.complete || view.end - self.at >= LOOKAHEAD)
+        });
+
+        if !reusable {
+            self.fill_line();
+            let view = &self.buf[self.at..];
+            let view = &view[..view.len().min(2 * LOOKAHEAD)];
+            let newline = view.iter().position(|&b| b == b'\n');
+            let end = newline.map_or(view.len(), |i| i + 1);
+            let text = match std::str::from_utf8(&view[..end]) {
+                Ok(text) => text,
+                Err(e) => std::str::from_utf8(&view[..e.valid_up_to()]).expect("valid up to here"),
+            };
+            let end = self.at + text.len();
+            let complete = newline.is_some() || (self.eof && end == self.buf.len());
+            self.view = Some(View { start: self.at, end, complete });
+        }
+
+        let view = self.view.as_ref().expect("view was just built");
+        // SAFETY: `start..end` was checked to be UTF-8 when the view was built
+        // and the buffer hasn't changed since, as `fill` drops the view. The
+        // cursor is on a character boundary within it.
+        unsafe { std::str::from_utf8_unchecked(&self.buf[self.at..view.end]) }
+    }
+
+    /// Runs `f` over the rest of the current line and advances past what it
+    /// consumed.
+    fn delegate<T>(&mut self, f: impl FnOnce(&mut Parser<'_>) -> T) -> T {
+        let (out, consumed) = {
+            let mut parser = Parser::new(self.line_view());
+            let out = f(&mut parser);
+            (out, parser.position())
+        };
+        self.at += consumed;
+        out
+    }
+
+    fn delegate_result<T>(&mut self, f: impl FnOnce(&mut Parser<'_>) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let position = self.position();
+        self.delegate(f).map_err(|mut e| {
+            e.at += position;
+            e
+        })
+    }
+
+    /// Checks why a token couldn't be extended: fine at a delimiter or a
+    /// clean end of stream, an error at invalid UTF-8 or after a failed read.
+    fn check_stopped(&mut self) -> Result<(), ParseError> {
+        if self.done() {
+            match &self.error {
+                Some(error) => Err(ParseError::new(self.position(), ParseErrorKind::Io(error.to_string()))),
+                None => Ok(()),
+            }
+        } else if self.line_view().is_empty() {
+            Err(ParseError::new(self.position(), ParseErrorKind::InvalidUtf8))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Current byte offset into the stream.
+    pub fn position(&self) -> usize {
+        self.base + self.at
+    }
+
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn expected(&mut self, expected: impl Into<String>) -> ParseError {
+        let kind = ParseErrorKind::Expected { expected: expected.into(), found: self.peek() };
+        ParseError::new(self.position(), kind)
+    }
+
+    pub fn peek(&mut self) -> Option<char> {
+        self.fill_to(4);
+        self.delegate(|p| p.peek())
+    }
+
+    pub fn peek_str(&mut self, s: &str) -> bool {
+        self.fill_to(s.len());
+        self.buf[self.at..].starts_with(s.as_bytes())
+    }
+
+    pub fn try_eat(&mut self, c: char) -> bool {
+        self.try_eat_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    pub fn eat(&mut self, c: char) {
+        if !self.try_eat(c) {
+            panic!("Tried to eat {} but found {:?}", c, self.peek());
+        }
+    }
+
+    pub fn try_eat_str(&mut self, s: &str) -> bool {
+        if self.peek_str(s) {
+            self.at += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn eat_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.eat(c);
+        }
+    }
+
+    pub fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.try_eat(c) {
+            Ok(())
+        } else {
+            Err(self.expected(format!("{c:?}")))
+        }
+    }
+
+    pub fn expect_str(&mut self, s: &str) -> Result<(), ParseError> {
+        if self.try_eat_str(s) {
+            Ok(())
+        } else {
+            Err(self.expected(format!("{s:?}")))
+        }
+    }
+
+    pub fn try_u64_base(&mut self, base: u32) -> Result<u64, ParseError> {
+        self.delegate_result(|p| p.try_u64_base(base))
+    }
+
+    pub fn u64_base(&mut self, base: u32) -> u64 {
+        self.try_u64_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u64(&mut self) -> Result<u64, ParseError> {
+        self.try_u64_base(10)
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        self.u64_base(10)
+    }
+
+    pub fn try_u64_auto(&mut self) -> Result<u64, ParseError> {
+        self.delegate_result(|p| p.try_u64_auto())
+    }
+
+    pub fn u64_auto(&mut self) -> u64 {
+        self.try_u64_auto().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u128_base(&mut self, base: u32) -> Result<u128, ParseError> {
+        self.delegate_result(|p| p.try_u128_base(base))
+    }
+
+    pub fn u128_base(&mut self, base: u32) -> u128 {
+        self.try_u128_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_u128(&mut self) -> Result<u128, ParseError> {
+        self.try_u128_base(10)
+    }
+
+    pub fn u128(&mut self) -> u128 {
+        self.u128_base(10)
+    }
+
+    pub fn try_i64_base(&mut self, base: u32) -> Result<i64, ParseError> {
+        self.delegate_result(|p| p.try_i64_base(base))
+    }
+
+    pub fn i64_base(&mut self, base: u32) -> i64 {
+        self.try_i64_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i64(&mut self) -> Result<i64, ParseError> {
+        self.try_i64_base(10)
+    }
+
+    pub fn i64(&mut self) -> i64 {
+        self.i64_base(10)
+    }
+
+    pub fn try_i64_auto(&mut self) -> Result<i64, ParseError> {
+        self.delegate_result(|p| p.try_i64_auto())
+    }
+
+    pub fn i64_auto(&mut self) -> i64 {
+        self.try_i64_auto().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i128_base(&mut self, base: u32) -> Result<i128, ParseError> {
+        self.delegate_result(|p| p.try_i128_base(base))
+    }
+
+    pub fn i128_base(&mut self, base: u32) -> i128 {
+        self.try_i128_base(base).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_i128(&mut self) -> Result<i128, ParseError> {
+        self.try_i128_base(10)
+    }
+
+    pub fn i128(&mut self) -> i128 {
+        self.i128_base(10)
+    }
+
+    pub fn try_int<T: Integer>(&mut self) -> Result<T, ParseError> {
+        self.delegate_result(|p| p.try_int())
+    }
+
+    pub fn int<T: Integer>(&mut self) -> T {
+        self.try_int().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_f64(&mut self) -> Result<f64, ParseError> {
+        self.delegate_result(|p| p.try_f64())
+    }
+
+    pub fn f64(&mut self) -> f64 {
+        self.try_f64().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn str_until(&mut self, delim: char) -> Result<String, ParseError> {
+        let mut out = String::new();
+        loop {
+            let before = self.position();
+            out.push_str(&self.delegate(|p| p.str_until(delim).to_owned()));
+            if self.position() == before || self.done() || self.peek() == Some(delim) {
+                self.check_stopped()?;
+                return Ok(out);
+            }
+        }
+    }
+
+    /// See [`Parser::parse_until`]. A value ending at `delim` must be on the
+    /// current line, while `None` parses everything left in the stream.
+    pub fn parse_until<T: FromStr>(&mut self, delim: Option<&str>) -> Result<T, ParseError> {
+        match delim {
+            // the view stops at the end of the line, so the delimiter can't
+            // be found past it
+            Some(_) => self.delegate_result(|p| p.parse_until(delim)),
+            None => self.attempt(|p| {
+                let start = p.position();
+                let text = p.rest()?;
+                text.parse().map_err(|_| {
+                    let kind = ParseErrorKind::Invalid { expected: std::any::type_name::<T>().to_owned(), text };
+                    ParseError::new(start, kind)
+                })
+            }),
+        }
+    }
+
+    /// Reads everything left in the stream.
+    pub fn rest(&mut self) -> Result<String, ParseError> {
+        let mut out = String::new();
+        loop {
+            let before = self.position();
+            out.push_str(&self.delegate(|p| p.rest().to_owned()));
+            if self.position() == before {
+                self.check_stopped()?;
+                return Ok(out);
+            }
+        }
+    }
+
+    /// Takes exactly `n` characters, which may span lines.
+    pub fn take(&mut self, n: usize) -> Result<String, ParseError> {
+        let start = self.position();
+        self.attempt(|p| {
+            let mut out = String::new();
+            let mut left = n;
+
+            while left > 0 {
+                let (piece, count) = p.delegate(|p| {
+                    let start = p.checkpoint();
+                    let count = p.rest().chars().take(left).count();
+                    p.restore(start);
+                    (p.take(count).expect("counted").to_owned(), count)
+                });
+                if count == 0 {
+                    let kind = ParseErrorKind::Expected { expected: format!("{n} more characters"), found: None };
+                    return Err(ParseError::new(start, kind));
+                }
+                out.push_str(&piece);
+                left -= count;
+            }
+
+            Ok(out)
+        })
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        loop {
+            let whole_line = self.delegate(|p| {
+                p.skip_whitespace();
+                p.done()
+            });
+            if !whole_line || self.done() {
+                break;
+            }
+        }
+    }
+
+    pub fn word(&mut self) -> Result<String, ParseError> {
+        let mut out = String::new();
+        loop {
+            let before = self.position();
+            out.push_str(&self.delegate(|p| p.word().to_owned()));
+            if self.position() == before || self.done() || self.peek().is_some_and(char::is_whitespace) {
+                self.check_stopped()?;
+                return Ok(out);
+            }
+        }
+    }
+
+    pub fn identifier(&mut self) -> Result<String, ParseError> {
+        self.delegate_result(|p| p.identifier().map(str::to_owned))
+    }
+
+    pub fn eol(&mut self) -> Result<(), ParseError> {
+        if self.done() || self.try_eat('\n') || self.try_eat_str("\r\n") {
+            Ok(())
+        } else {
+            Err(self.expected("end of line"))
+        }
+    }
+
+    /// Takes the rest of the current line and moves past its line ending.
+    pub fn line(&mut self) -> Result<String, ParseError> {
+        let mut line = String::new();
+        loop {
+            let before = self.position();
+            line.push_str(&self.delegate(|p| p.str_until('\n').to_owned()));
+            if self.try_eat('\n') {
+                break;
+            }
+            if self.position() == before {
+                self.check_stopped()?;
+                break;
+            }
+        }
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        Ok(line)
+    }
+
+    pub fn done(&mut self) -> bool {
+        self.fill_to(1);
+        self.at == self.buf.len()
+    }
+
+    pub fn expect_done(&mut self) -> Result<(), ParseError> {
+        if let Some(error) = &self.error {
+            Err(ParseError::new(self.position(), ParseErrorKind::Io(error.to_string())))
+        } else if self.done() {
+            Ok(())
+        } else if self.line_view().is_empty() {
+            Err(ParseError::new(self.position(), ParseErrorKind::InvalidUtf8))
+        } else {
+            Err(self.expected("end of input"))
+        }
+    }
+
+    pub fn span_since(&self, checkpoint: StreamCheckpoint) -> Span {
+        Span::new(checkpoint.0, self.position())
+    }
+
+    /// Runs `f` and records the span of input it consumed.
+    pub fn spanned<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<Spanned<T>, ParseError> {
+        let checkpoint = self.checkpoint();
+        let value = f(self)?;
+        Ok(Spanned::new(value, self.span_since(checkpoint)))
+    }
+
+    pub fn checkpoint(&self) -> StreamCheckpoint {
+        StreamCheckpoint(self.position())
+    }
+
+    /// Panics if the checkpoint has already fallen out of the window, or
+    /// lies past anything this parser has read.
+    pub fn restore(&mut self, checkpoint: StreamCheckpoint) {
+        let at = checkpoint.position();
+        assert!(at >= self.base, "checkpoint at {} has left the {} byte window", at, self.window);
+        let read = self.base + self.buf.len();
+        assert!(at <= read, "checkpoint at {at} is past the {read} bytes read so far");
+        self.at = at - self.base;
+    }
+
+    /// Runs `f`, rewinding to where it started if it fails. Everything `f`
+    /// reads stays buffered until it returns, however far that goes.
+    pub fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let checkpoint = self.checkpoint();
+        self.pins.push(checkpoint.position());
+        let out = f(self);
+        self.pins.pop();
+        out.inspect_err(|_| self.restore(checkpoint))
+    }
+
+    pub fn optional<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Option<T> {
+        self.attempt(f).ok()
+    }
+
+    pub fn many<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Vec<T> {
+        let mut out = Vec::new();
+        loop {
+            let before = self.position();
+            match self.attempt(&mut f) {
+                Ok(v) => out.push(v),
+                Err(_) => break,
+            }
+            if self.position() == before {
+                break;
+            }
+        }
+        out
+    }
+
+    pub fn many1<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let first = self.attempt(&mut f)?;
+        let mut out = vec![first];
+        out.extend(self.many(f));
+        Ok(out)
+    }
+
+    pub fn sep_by<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>, sep: &str) -> Vec<T> {
+        match self.attempt(&mut f) {
+            Ok(first) => {
+                let mut out = vec![first];
+                out.extend(self.many(|p| {
+                    p.expect_str(sep)?;
+                    f(p)
+                }));
+                out
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn sep_by1<T>(&mut self, mut f: impl FnMut(&mut Self) -> Result<T, ParseError>, sep: &str) -> Result<Vec<T>, ParseError> {
+        let checkpoint = self.checkpoint();
+        let out = self.sep_by(&mut f, sep);
+        if out.is_empty() {
+            self.restore(checkpoint);
+            Err(self.attempt(f).err().unwrap_or_else(|| self.expected("an item")))
+        } else {
+            Ok(out)
+        }
+    }
+
+    pub fn delimited<T>(
+        &mut self,
+        open: &str,
+        f: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+        close: &str,
+    ) -> Result<T, ParseError> {
+        self.attempt(|p| {
+            p.expect_str(open)?;
+            let out = f(p)?;
+            p.expect_str(close)?;
+            Ok(out)
+        })
+    }
+
+    pub fn list_of<T>(
+        &mut self,
+        open: &str,
+        sep: &str,
+        close: &str,
+        f: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.delimited(open, |p| Ok(p.sep_by(f, sep)), close)
+    }
+
+    /// See [`Parser::alt`].
+    pub fn alt<T>(&mut self, alternatives: &mut [&mut StreamAlternative<'_, R, T>]) -> Result<T, ParseError> {
+        let mut furthest: Option<ParseError> = None;
+        for f in alternatives {
+            match self.attempt(f) {
+                Ok(v) => return Ok(v),
+                Err(e) => if furthest.as_ref().is_none_or(|f| e.at >= f.at) {
+                    furthest = Some(e);
+                },
+            }
+        }
+        Err(furthest.unwrap_or_else(|| self.expected("one of no alternatives")))
+    }
+
+    /// Parses one line with an ordinary [`Parser`], which must consume all
+    /// of it.
+    pub fn parse_line<T>(&mut self, f: impl FnOnce(&mut Parser<'_>) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        self.delegate_result(|p| {
+            let mut line = p.line();
+            let out = f(&mut line)?;
+            line.expect_done()?;
+            Ok(out)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out at most `n` bytes per read, to exercise refilling.
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(self.1);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn test_numbers_across_reads() {
+        let input = "+1\n-2\n+3\n+1\n12345678901234567890\n";
+        let mut parser = StreamParser::new(Trickle(input.as_bytes(), 1));
+        let numbers = parser.sep_by(|p| p.try_int::<i128>(), "\n");
+        assert_eq!(numbers, vec![1, -2, 3, 1, 12345678901234567890]);
+        assert!(parser.eol().is_ok());
+        assert!(parser.expect_done().is_ok());
+    }
+
+    #[test]
+    fn test_same_api_as_parser() {
+        let input = "[1518-11-01 00:05] falls asleep\n#1 @ 1,3: 4x4\n  \n\n é 1.5";
+        let mut parser = StreamParser::new(Trickle(input.as_bytes(), 3));
+        parser.eat('[');
+        assert_eq!(parser.str_until(']').as_deref(), Ok("1518-11-01 00:05"));
+        parser.eat_str("] ");
+        assert_eq!(parser.word().as_deref(), Ok("falls"));
+        assert!(parser.peek_str(" asleep\n#1"));
+        assert_eq!(parser.line().as_deref(), Ok(" asleep"));
+
+        parser.expect('#').unwrap();
+        assert_eq!(parser.u64(), 1);
+        parser.expect_str(" @ ").unwrap();
+        assert_eq!(parser.parse_until::<u32>(Some(",")), Ok(1));
+        parser.eat(',');
+        assert_eq!(parser.parse_until::<String>(Some("\n")), Ok("3: 4x4".to_string()));
+
+        parser.skip_whitespace();
+        assert_eq!(parser.peek(), Some('é'));
+        parser.eat('é');
+        parser.skip_whitespace();
+        assert_eq!(parser.f64(), 1.5);
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_parse_until_end() {
+        let mut parser = StreamParser::new(Trickle(b"12\n34", 1));
+        assert_eq!(parser.parse_until::<u32>(None).unwrap_err().at, 0);
+        assert_eq!(parser.position(), 0);
+        assert_eq!(parser.parse_until::<String>(None).as_deref(), Ok("12\n34"));
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_long_line() {
+        let polymer = "dabAcCaCBAcCcaDA".repeat(2_000);
+        let mut parser = StreamParser::with_window(Trickle(polymer.as_bytes(), 1000), 16);
+        assert_eq!(parser.word().as_deref(), Ok(polymer.as_str()));
+        assert!(parser.done());
+
+        let input = format!("{polymer}\r\nx");
+        let mut parser = StreamParser::with_window(Trickle(input.as_bytes(), 1000), 16);
+        assert_eq!(parser.line().as_deref(), Ok(polymer.as_str()));
+        assert_eq!(parser.line().as_deref(), Ok("x"));
+        assert!(parser.done());
+        assert!(parser.buf.len() <= 3 * CHUNK);
+    }
+
+    #[test]
+    fn test_many_tokens_on_one_line() {
+        // each token reuses the line view rather than rescanning 8 KiB
+        let input = "1,".repeat(200_000);
+        let mut parser = StreamParser::new(Trickle(input.as_bytes(), 5000));
+        let ones = parser.sep_by(|p| p.try_u64(), ",");
+        assert_eq!(ones.len(), 200_000);
+        assert_eq!(parser.parse_until::<String>(None), Ok(",".to_string()));
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_combinators() {
+        let input = "[1,[2,3]]\n0x1f 170141183460469231731687303715884105727 -5 abcdé\nrest\nof it";
+        let mut parser = StreamParser::new(Trickle(input.as_bytes(), 2));
+        assert_eq!(parser.value().to_string(), "[1,[2,3]]");
+        parser.eol().unwrap();
+        assert_eq!(parser.try_u64_auto(), Ok(31));
+        parser.skip_whitespace();
+        assert_eq!(parser.u128(), i128::MAX as u128);
+        parser.skip_whitespace();
+        assert_eq!(parser.i128(), -5);
+        parser.skip_whitespace();
+
+        let abc = parser.spanned(|p| p.take(3)).unwrap();
+        assert_eq!((abc.value.as_str(), abc.span), ("abc", Span::new(58, 61)));
+        assert_eq!(parser.take(4).as_deref(), Ok("dé\nr"));
+        assert!(parser.attempt(|p| p.take(20)).is_err());
+        assert_eq!(parser.rest().as_deref(), Ok("est\nof it"));
+
+        let mut parser = StreamParser::new("[4;5;6] x".as_bytes());
+        assert_eq!(parser.list_of("[", ";", "]", |p| p.try_u64()), Ok(vec![4, 5, 6]));
+        assert!(parser.many1(|p| p.try_u64()).is_err());
+        assert!(parser.sep_by1(|p| p.try_u64(), ",").is_err());
+        parser.eat(' ');
+        let word = parser.alt(&mut [&mut |p| p.expect('y').map(|_| "y"), &mut |p| p.expect('x').map(|_| "x")]);
+        assert_eq!(word, Ok("x"));
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_str_until_spans_lines() {
+        let mut parser = StreamParser::new(Trickle(b"ab\ncd;ef", 2));
+        assert_eq!(parser.str_until(';').as_deref(), Ok("ab\ncd"));
+        parser.eat(';');
+        assert_eq!(parser.str_until(';').as_deref(), Ok("ef"));
+        assert!(parser.done());
+    }
+
+    #[test]
+    fn test_errors_use_stream_offsets() {
+        let mut parser = StreamParser::new(Trickle(b"10\n20\nx", 1));
+        parser.u64();
+        parser.eat('\n');
+        parser.u64();
+        parser.eat('\n');
+        assert_eq!(parser.try_u64().unwrap_err().at, 6);
+        assert_eq!(parser.expect_done().unwrap_err().at, 6);
+        assert_eq!(parser.identifier().as_deref(), Ok("x"));
+    }
+
+    #[test]
+    fn test_checkpoint_in_window() {
+        let input = "1,".repeat(20_000);
+        let mut parser = StreamParser::with_window(input.as_bytes(), 16);
+
+        let checkpoint = parser.checkpoint();
+        assert_eq!(parser.attempt(|p| {
+            p.expect_str("1,1,")?;
+            p.expect('x')
+        }).unwrap_err().at, 4);
+        assert_eq!(parser.checkpoint(), checkpoint);
+
+        let ones = parser.many(|p| {
+            let v = p.try_u64()?;
+            p.expect(',')?;
+            Ok(v)
+        });
+        assert_eq!(ones.len(), 20_000);
+        assert!(parser.done());
+        assert!(parser.buf.len() <= 3 * CHUNK);
+    }
+
+    #[test]
+    fn test_failed_attempt_longer_than_window() {
+        let mut input = (0..100_000).map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+        input = format!("[{input},x]");
+        let mut parser = StreamParser::with_window(Trickle(input.as_bytes(), 1000), 16);
+        assert!(parser.list_of("[", ",", "]", |p| p.try_u64()).is_err());
+        assert_eq!(parser.position(), 0);
+        assert_eq!(parser.peek(), Some('['));
+
+        let long = "a".repeat(50_000);
+        let mut parser = StreamParser::with_window(Trickle(long.as_bytes(), 1000), 16);
+        assert!(parser.take(50_001).is_err());
+        assert_eq!(parser.take(50_000).as_deref(), Ok(long.as_str()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_checkpoint_out_of_window() {
+        let input = "1,".repeat(20_000);
+        let mut parser = StreamParser::with_window(input.as_bytes(), 16);
+        let checkpoint = parser.checkpoint();
+        parser.eat('1');
+        while !parser.done() {
+            parser.u64();
+            parser.eat(',');
+        }
+        parser.restore(checkpoint);
+    }
+
+    #[test]
+    #[should_panic(expected = "checkpoint at 5 is past the 3 bytes read so far")]
+    fn test_checkpoint_past_input() {
+        let mut parser = StreamParser::new("abc".as_bytes());
+        parser.done();
+        parser.restore(StreamCheckpoint(5));
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let input = b"ab\xffcd\nok\n\xff";
+        let mut parser = StreamParser::new(Trickle(input, 2));
+        assert_eq!(parser.word().unwrap_err(), ParseError::new(2, ParseErrorKind::InvalidUtf8));
+        assert_eq!(parser.expect_done().unwrap_err().kind, ParseErrorKind::InvalidUtf8);
+        assert!(parser.line().is_err());
+        assert!(parser.rest().is_err());
+        assert_eq!(parser.position(), 2);
+
+        let mut parser = StreamParser::new(&input[6..]);
+        assert_eq!(parser.line().as_deref(), Ok("ok"));
+        assert_eq!(parser.str_until(';').unwrap_err().kind, ParseErrorKind::InvalidUtf8);
+    }
+
+    /// Hands out its bytes, then fails instead of reporting the end.
+    struct Truncated<'a>(&'a [u8]);
+
+    impl Read for Truncated<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("connection reset"));
+            }
+            let n = self.0.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_truncated_read() {
+        let mut parser = StreamParser::new(Truncated(b"done\npartial li"));
+        assert_eq!(parser.line().as_deref(), Ok("done"));
+        assert!(matches!(parser.line().unwrap_err().kind, ParseErrorKind::Io(_)));
+
+        let mut parser = StreamParser::new(Truncated(b"one two"));
+        assert_eq!(parser.word().as_deref(), Ok("one"));
+        parser.skip_whitespace();
+        assert!(parser.word().is_err());
+        assert!(StreamParser::new(Truncated(b"abc")).rest().is_err());
+    }
+
+    #[test]
+    fn test_io_error() {
+        let mut parser = StreamParser::new(Failing);
+        assert!(parser.done());
+        let error = parser.expect_done().unwrap_err();
+        assert!(matches!(error.kind, ParseErrorKind::Io(_)));
+        assert!(parser.io_error().is_some());
+    }
+}
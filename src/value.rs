@@ -0,0 +1,160 @@
+use std::{cmp::Ordering, fmt, io::Read, str::FromStr};
+
+use crate::{
+    parser::{ParseError, Parser},
+    streamparser::StreamParser,
+};
+
+/// An integer or an arbitrarily nested list of values, like
+/// `[1,[2,[3,[4,[5,6,7]]]],8,9]`.
+///
+/// Values order by AoC 2022 day 13's packet rules: integers compare
+/// numerically, lists compare element by element, and an integer compared
+/// with a list is treated as a one-element list. Equality follows the same
+/// rules, so `2` equals `[[2]]`.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int(i64),
+    List(Vec<Value>),
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => a.iter().cmp(b.iter()),
+            (Value::Int(_), Value::List(b)) => std::slice::from_ref(self).iter().cmp(b.iter()),
+            (Value::List(a), Value::Int(_)) => a.iter().cmp(std::slice::from_ref(other).iter()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{v}"),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let value = parser.try_value()?;
+        parser.expect_done()?;
+        Ok(value)
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn try_value(&mut self) -> Result<Value, ParseError> {
+        if self.peek() == Some('[') {
+            self.list_of("[", ",", "]", Parser::try_value).map(Value::List)
+        } else {
+            self.try_i64().map(Value::Int)
+        }
+    }
+
+    pub fn value(&mut self) -> Value {
+        self.try_value().unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+impl<R: Read> StreamParser<R> {
+    pub fn try_value(&mut self) -> Result<Value, ParseError> {
+        if self.peek() == Some('[') {
+            self.list_of("[", ",", "]", StreamParser::try_value).map(Value::List)
+        } else {
+            self.try_i64().map(Value::Int)
+        }
+    }
+
+    pub fn value(&mut self) -> Value {
+        self.try_value().unwrap_or_else(|e| panic!("{e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        use Value::*;
+
+        assert!(matches!(value("[]"), List(v) if v.is_empty()));
+        assert!(matches!(value("-3"), Int(-3)));
+        assert!(matches!(value("[[1],4]"), List(v) if matches!(&v[..], [List(_), Int(4)])));
+
+        assert!("[1,2".parse::<Value>().is_err());
+        assert!("[1,,2]".parse::<Value>().is_err());
+        assert!("[1] ".parse::<Value>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for s in ["[1,[2,[3,[4,[5,6,7]]]],8,9]", "[[[]]]", "[]", "[10,-1,[]]", "7"] {
+            assert_eq!(value(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let in_order = [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]"),
+            ("[[1],[2,3,4]]", "[[1],4]"),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]"),
+            ("[]", "[3]"),
+            ("[1,[2,[3,[4,[5,6,0]]]],8,9]", "[1,[2,[3,[4,[5,6,7]]]],8,9]"),
+        ];
+        for (a, b) in in_order {
+            assert!(value(a) < value(b), "{a} < {b}");
+        }
+
+        let out_of_order = [("[9]", "[[8,7,6]]"), ("[7,7,7,7]", "[7,7,7]"), ("[[[]]]", "[[]]")];
+        for (a, b) in out_of_order {
+            assert!(value(a) > value(b), "{a} > {b}");
+        }
+
+        assert_eq!(value("2"), value("[[2]]"));
+    }
+
+    #[test]
+    fn test_parser_value() {
+        let mut parser = Parser::new("[1,[2]]\n[3]");
+        assert_eq!(parser.value().to_string(), "[1,[2]]");
+        parser.eat('\n');
+        assert_eq!(parser.value().to_string(), "[3]");
+        assert!(parser.done());
+    }
+}